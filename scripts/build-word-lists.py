@@ -0,0 +1,112 @@
+#!/usr/bin/env python3
+"""Build the word lists for lengths other than 5 in words/.
+
+The words come from the word lists packaged in these crates:
+
+  petname     Dustin Kirkland's petname word lists (Apache-2.0)
+  names       adjectives and nouns for generating names (MIT)
+  bip39       the BIP-39 English wordlist (CC0-1.0)
+  stop-words  English stop words from Stopwords ISO (MIT OR Apache-2.0)
+
+Answers ("La") are the words from petname's small and medium lists, names
+and bip39. Other allowed guesses ("Ta") are the rest of the words from
+petname's large lists and the stop words. Most of the longer guesses come
+from petname's large lists, which include many rare un- and non- words.
+
+Usage: scripts/build-word-lists.py [CRATES_DIR]
+
+Crates are unpacked in CRATES_DIR, downloading any not already there from
+crates.io. Without CRATES_DIR, a temporary directory is used.
+"""
+
+import glob
+import io
+import json
+import os
+import re
+import sys
+import tarfile
+import tempfile
+import urllib.request
+
+CRATES = {
+    "petname": "2.0.2",
+    "names": "0.14.0",
+    "bip39": "2.2.2",
+    "stop-words": "0.9.0",
+}
+
+LENGTHS = [4, 6, 7, 8, 9, 10, 11]
+
+WORDS_DIR = os.path.join(os.path.dirname(os.path.abspath(__file__)), "..", "words")
+
+
+def unpack(crates_dir, name, version):
+    """Get the directory of a crate's sources, downloading it if needed."""
+    path = os.path.join(crates_dir, f"{name}-{version}")
+    if not os.path.isdir(path):
+        url = f"https://static.crates.io/crates/{name}/{name}-{version}.crate"
+        print(f"downloading {url}", file=sys.stderr)
+        with urllib.request.urlopen(url) as response:
+            data = response.read()
+        with tarfile.open(fileobj=io.BytesIO(data), mode="r:gz") as archive:
+            archive.extractall(crates_dir)
+    return path
+
+
+def read_words(paths):
+    """Words of lowercase letters only from files with one word per line."""
+    words = set()
+    for path in paths:
+        with open(path) as file:
+            for line in file:
+                word = line.strip().lower()
+                if re.fullmatch("[a-z]+", word):
+                    words.add(word)
+    return words
+
+
+def build(crates_dir):
+    petname = unpack(crates_dir, "petname", CRATES["petname"])
+    names = unpack(crates_dir, "names", CRATES["names"])
+    bip39 = unpack(crates_dir, "bip39", CRATES["bip39"])
+    stop_words = unpack(crates_dir, "stop-words", CRATES["stop-words"])
+
+    with open(os.path.join(bip39, "src/language/english.rs")) as file:
+        bip39_words = set(re.findall(r'"([a-z]+)"', file.read()))
+    common = (
+        read_words(
+            glob.glob(os.path.join(petname, "words/small/*"))
+            + glob.glob(os.path.join(petname, "words/medium/*"))
+            + glob.glob(os.path.join(names, "data/*"))
+        )
+        | bip39_words
+    )
+
+    with open(os.path.join(stop_words, "src/iso/stopwords-iso.json")) as file:
+        stop = {w for w in json.load(file)["en"] if re.fullmatch("[a-z]+", w)}
+    rare = read_words(glob.glob(os.path.join(petname, "words/large/*"))) | stop
+
+    for length in LENGTHS:
+        answers = sorted(w for w in common if len(w) == length)
+        guesses = sorted(w for w in rare - common if len(w) == length)
+        for kind, words in [("La", answers), ("Ta", guesses)]:
+            path = os.path.join(WORDS_DIR, f"wordle-{kind}-{length}.txt")
+            with open(path, "w") as file:
+                file.write("\n".join(words) + "\n")
+        print(f"{length}: {len(answers)} answers, {len(guesses)} other guesses")
+
+
+def main():
+    if len(sys.argv) > 2:
+        sys.exit(__doc__)
+    if len(sys.argv) == 2:
+        os.makedirs(sys.argv[1], exist_ok=True)
+        build(sys.argv[1])
+    else:
+        with tempfile.TemporaryDirectory() as crates_dir:
+            build(crates_dir)
+
+
+if __name__ == "__main__":
+    main()
@@ -1,59 +1,469 @@
+use std::collections::VecDeque;
 use std::io::{self, Write};
+use std::mem;
+use std::rc::Rc;
+use std::sync::{Arc, OnceLock};
+use std::thread;
 use termion::event::Key;
 
-use crate::{util, ColorScheme, Game};
+use wordlers::{CursorMove, Game, GuessError, HardModeViolation, Hint, Mode, RestoreError, Words};
+
+use crate::{
+    util, Action, ColorScheme, Frame, GameRecord, GameView, Help, Menu, MenuEvent, Options,
+    Palette, Renderer, Review, Stats, Storage,
+};
+
+/// What the app is showing, apart from any overlay.
+enum State {
+    /// The start menu, where settings are chosen before a game is created.
+    Menu(Menu),
+    Playing(Game),
+}
+
+/// A screen drawn over the board.
+enum Overlay {
+    Stats(Stats),
+    Review(Review),
+    Help(Help),
+}
 
 pub struct App<W: Write> {
     screen: W,
-    game: Game,
+    /// Writes each frame drawn to the screen.
+    renderer: Box<dyn Renderer>,
+    /// Size (w, h) to draw at, or `None` to fit the terminal.
+    size: Option<(u16, u16)>,
+    state: State,
+    /// Draws the game being played, and animates it.
+    view: GameView,
+    options: Options,
+    words: Rc<Words>,
+    storage: Option<Storage>,
+    records: Vec<GameRecord>,
+    overlay: Option<Overlay>,
+    /// Whether a saved game is being shown, waiting for the player to
+    /// choose whether to resume it.
+    resuming: bool,
+    /// Whether a game saved with other words is being kept for when those
+    /// words are played again. Games aren't saved over it.
+    keeping_save: bool,
     color_scheme: ColorScheme,
+    /// Color scheme loaded from a file, if any.
+    custom_colors: Option<ColorScheme>,
+    /// Keys pressed during an animation, handled once it's over.
+    queued_keys: VecDeque<Key>,
+    /// Review of the last finished game, worked out on another thread from
+    /// when the game ends.
+    review: Arc<OnceLock<Review>>,
 }
 
 impl<W: Write> App<W> {
-    pub fn new(screen: W) -> io::Result<Self> {
-        let game = Game::new();
+    pub fn new(
+        screen: W,
+        options: Options,
+        words: Words,
+        custom_colors: Option<ColorScheme>,
+        renderer: Box<dyn Renderer>,
+        storage: Option<Storage>,
+    ) -> io::Result<Self> {
+        let mut problems = Vec::new();
+        let records = match &storage {
+            Some(storage) => storage.load_records(&mut problems)?,
+            None => Vec::new(),
+        };
+
+        let words = Rc::new(words);
+        words.prepare_opening_guesses();
+
+        // Offer to resume a game left unfinished last time, or else start at
+        // the menu. A save made with other words is kept to be resumed when
+        // they're played again, but one that can't be restored is thrown
+        // away, so the problem is only reported once. Damaged statistics are
+        // reported too, but kept for the player to fix.
+        let mut menu = Menu::default();
+        match &problems[..] {
+            [] => {}
+            [problem] => menu.set_message(&format!("Skipped {problem}")),
+            [problem, rest @ ..] => {
+                menu.set_message(&format!("Skipped {problem} and {} more", rest.len()))
+            }
+        }
+        let mut view = GameView::default();
+        let mut saved_game = None;
+        let mut keeping_save = false;
+        if let Some(storage) = &storage {
+            if let Some(save) = storage.load_game()? {
+                match Game::restore(&save, Rc::clone(&words)) {
+                    Ok(game) => {
+                        view.set_message(&format!(
+                            "Resume saved game?\n{}: resume, {}: new game",
+                            options.keys.describe(Action::Submit),
+                            options.keys.describe(Action::Discard),
+                        ));
+                        saved_game = Some(game);
+                    }
+                    Err(e @ (RestoreError::OtherLength(_) | RestoreError::OtherAnswers)) => {
+                        menu.set_message(&format!("Kept a game {e}, to resume later"));
+                        keeping_save = true;
+                    }
+                    Err(e) => {
+                        storage.clear_game()?;
+                        menu.set_message(&format!("Discarded saved game: {e}"));
+                    }
+                }
+            }
+        }
+        let resuming = saved_game.is_some();
+        let state = match saved_game {
+            Some(game) => State::Playing(game),
+            None => State::Menu(menu),
+        };
+
+        let color_scheme = color_scheme_for(&options, custom_colors.as_ref());
         let mut app = Self {
             screen,
-            game,
-            color_scheme: ColorScheme::from(catppuccin::PALETTE.mocha),
+            renderer,
+            size: None,
+            state,
+            view,
+            options,
+            words,
+            storage,
+            records,
+            overlay: None,
+            resuming,
+            keeping_save,
+            color_scheme,
+            custom_colors,
+            queued_keys: VecDeque::new(),
+            review: Arc::default(),
         };
 
         // Hide cursor on init.
         write!(app.screen, "{}", termion::cursor::Hide)?;
-        app.repaint()?;
         Ok(app)
     }
 
-    fn restart(&mut self) {
-        self.game = Game::new();
+    /// Draw at a fixed size (w, h) instead of fitting the terminal, as
+    /// snapshot tests do.
+    #[cfg(test)]
+    pub fn set_size(&mut self, size: (u16, u16)) {
+        self.size = Some(size);
+    }
+
+    /// The game being played. Only called once a game has been created.
+    fn game(&self) -> &Game {
+        match &self.state {
+            State::Playing(game) => game,
+            State::Menu(_) => unreachable!("no game has been created"),
+        }
+    }
+
+    /// The game being played, mutably. Only called once a game has been
+    /// created.
+    fn game_mut(&mut self) -> &mut Game {
+        match &mut self.state {
+            State::Playing(game) => game,
+            State::Menu(_) => unreachable!("no game has been created"),
+        }
+    }
+
+    /// Start a new game with the current options. The word lists are
+    /// reloaded first if the word length has changed; if that fails, the
+    /// menu is shown with the reason.
+    fn start_game(&mut self) -> io::Result<()> {
+        if self.words.length() != self.options.length {
+            match Words::load(
+                self.options.length,
+                self.options.answers.as_deref(),
+                self.options.guesses.as_deref(),
+            ) {
+                Ok(words) => {
+                    words.prepare_opening_guesses();
+                    self.words = Rc::new(words);
+                }
+                Err(e) => {
+                    self.show_menu(Some(&e.to_string()));
+                    return Ok(());
+                }
+            }
+        }
+
+        // The daily puzzle can only be played once, so fall back to a random
+        // game if today's has already been played.
+        let mut settings = self.options.settings();
+        let mut already_played = None;
+        if let (Mode::Daily, Some(storage)) = (settings.mode, &self.storage) {
+            let today = Words::todays_puzzle_number();
+            if storage.last_daily_played()? == Some(today) {
+                settings.mode = Mode::Random;
+                already_played = Some(today);
+            }
+        }
+
+        let game = Game::new(&settings, Rc::clone(&self.words));
+        self.view = GameView::default();
+        match already_played {
+            Some(n) => self
+                .view
+                .set_message(&format!("#{n} already played!\nHere's a random word.")),
+            // Say which daily puzzle is being played.
+            None if game.puzzle_number().is_some() => self.view.set_message(&game.title()),
+            None => {}
+        }
+        self.state = State::Playing(game);
+        Ok(())
+    }
+
+    /// Go back to the menu, with a message if given.
+    fn show_menu(&mut self, message: Option<&str>) {
+        let mut menu = Menu::default();
+        if let Some(message) = message {
+            menu.set_message(message);
+        }
+        self.state = State::Menu(menu);
+    }
+
+    fn show_stats(&mut self) {
+        self.overlay = Some(Overlay::Stats(Stats::from_records(&self.records)));
+    }
+
+    /// Whether the game can be reviewed: it must be over, and reviews only
+    /// cover games with a single board.
+    fn can_review(&self) -> bool {
+        matches!(&self.state, State::Playing(game)
+            if game.has_won().is_some() && game.board_count() == 1)
+    }
+
+    /// Start reviewing the game that just ended on another thread, so that
+    /// the review is ready by the time it's asked for.
+    fn prepare_review(&mut self) {
+        self.review = Arc::default();
+        if self.can_review() {
+            let review = Arc::clone(&self.review);
+            let words = Words::clone(&self.words);
+            let feedback = self.game().feedback(0);
+            thread::spawn(move || {
+                review.get_or_init(|| Review::new(&words, &feedback));
+            });
+        }
+    }
+
+    /// Show the review of the game that just ended, waiting for it to be
+    /// worked out if it isn't yet.
+    fn show_review(&mut self) {
+        let review = Arc::clone(&self.review);
+        let review = review.get_or_init(|| Review::new(&self.words, &self.game().feedback(0)));
+        self.overlay = Some(Overlay::Review(review.clone()));
+    }
+
+    /// Save the result of the game that just ended.
+    fn record_result(&mut self, won: bool) -> io::Result<()> {
+        let record = GameRecord {
+            date: chrono::Local::now().date_naive(),
+            won,
+            guesses: self.game().guesses_used(),
+            answer: self.game().answers().join(","),
+        };
+
+        if let Some(storage) = &self.storage {
+            storage.record_game(&record)?;
+            if let Some(n) = self.game().puzzle_number() {
+                storage.record_daily_played(n)?;
+            }
+        }
+        self.records.push(record);
+        Ok(())
+    }
+
+    /// Give up on an unfinished game, so another can be started. A game
+    /// with guesses made counts as a loss, so giving up can't be used to
+    /// protect a streak. Today's puzzle, numbered `today`, can't be given
+    /// up at all, or it could be replayed knowing the earlier feedback.
+    /// Returns whether the game was given up.
+    fn abandon_game(&mut self, today: u32) -> io::Result<bool> {
+        let game = self.game();
+        if game.has_won().is_some() {
+            return Ok(true);
+        }
+        if game.puzzle_number() == Some(today) {
+            return Ok(false);
+        }
+        if game.guesses_used() > 0 {
+            self.record_result(false)?;
+        }
+        Ok(true)
+    }
+
+    /// Get the action bound to a key, if any.
+    pub fn action(&self, key: Key) -> Option<Action> {
+        self.options.keys.action(key)
     }
 
     pub fn handle_key(&mut self, key: Key) -> io::Result<()> {
-        // After game is over, accept ENTER to restart.
-        if self.game.has_won().is_some() {
-            return match key {
-                Key::Char('\n') => {
-                    self.restart();
+        let action = self.action(key);
+
+        // Themes can be switched at any time, without closing an overlay.
+        if action == Some(Action::Theme) {
+            self.next_theme();
+            return self.repaint();
+        }
+
+        // Wait for an animation to finish before handling anything else.
+        if self.is_animating() {
+            self.queued_keys.push_back(key);
+            return Ok(());
+        }
+
+        // Any key closes an overlay, but the review can be opened from the
+        // statistics shown after a game.
+        if let Some(overlay) = self.overlay.take() {
+            if let (Overlay::Stats(_), true, Some(Action::Review)) =
+                (overlay, self.can_review(), action)
+            {
+                self.show_review();
+            }
+            return self.repaint();
+        }
+
+        if action == Some(Action::Stats) {
+            self.show_stats();
+            return self.repaint();
+        }
+
+        if action == Some(Action::Help) {
+            let help = match &self.state {
+                State::Menu(_) => Help::for_menu(&self.options),
+                State::Playing(game) => Help::new(game, &self.options.keys),
+            };
+            self.overlay = Some(Overlay::Help(help));
+            return self.repaint();
+        }
+
+        if let State::Menu(menu) = &mut self.state {
+            let has_custom_theme = self.custom_colors.is_some();
+            match action.and_then(|a| menu.handle_action(a, &mut self.options, has_custom_theme)) {
+                Some(MenuEvent::Moved) => {}
+                Some(MenuEvent::Changed(setting)) => {
+                    self.color_scheme =
+                        color_scheme_for(&self.options, self.custom_colors.as_ref());
+                    if let Err(e) = self.options.save_setting(setting) {
+                        menu.set_message(&format!("Couldn't save settings: {e}"));
+                    }
+                }
+                Some(MenuEvent::Play) => self.start_game()?,
+                Some(MenuEvent::Stats) => self.show_stats(),
+                None => return Ok(()),
+            }
+            return self.repaint();
+        }
+
+        // A saved game is shown until the player chooses to resume it or
+        // go to the menu for a new one. Either way, it's no longer needed on
+        // disk.
+        if self.resuming {
+            match action {
+                Some(Action::Submit) => self.view.clear_message(),
+                Some(Action::NewGame | Action::Discard) => {
+                    if !self.abandon_game(Words::todays_puzzle_number())? {
+                        self.view.set_message(&format!(
+                            "Today's puzzle can't be skipped!\n{}: resume",
+                            self.options.keys.describe(Action::Submit)
+                        ));
+                        return self.repaint();
+                    }
+                    self.show_menu(None);
+                }
+                _ => return Ok(()),
+            }
+            self.resuming = false;
+            if let Some(storage) = &self.storage {
+                storage.clear_game()?;
+            }
+            return self.repaint();
+        }
+
+        // After game is over, accept ENTER to go back to the menu, start a
+        // new game with the same settings, or share the result.
+        if self.game().has_won().is_some() {
+            return match action {
+                Some(Action::Submit) => {
+                    self.show_menu(None);
+                    self.repaint()
+                }
+                Some(Action::NewGame) => {
+                    self.start_game()?;
+                    self.repaint()
+                }
+                Some(Action::Copy) => {
+                    self.copy_share_text()?;
+                    self.set_game_over_message("Copied result to clipboard!");
+                    self.repaint()
+                }
+                Some(Action::Review) if self.can_review() => {
+                    self.show_review();
+                    self.repaint()
+                }
+                Some(Action::Save) => {
+                    let status = match &self.storage {
+                        Some(storage) => {
+                            let path = storage.save_share_text(
+                                &self.game().share_text(self.color_scheme.squares),
+                            )?;
+                            format!("Saved to {}", path.display())
+                        }
+                        None => "Nowhere to save result!".into(),
+                    };
+                    self.set_game_over_message(&status);
                     self.repaint()
                 }
                 _ => Ok(()),
             };
         }
 
-        if match key {
-            Key::Char('\n') => self.game.try_submit_guess(),
-            Key::Char(c) => self.game.try_accept_letter(c),
-            Key::Backspace => self.game.try_delete_letter(),
+        // Prompts aren't shown while playing, so their keys type instead.
+        let action = action.filter(|action| !action.is_prompt());
+
+        // Typing clears the message, but moving the cursor doesn't.
+        if let (Some(Action::Delete | Action::Clear), _) | (None, Key::Char(_)) = (action, key) {
+            self.view.clear_message();
+        }
+
+        let game = self.game_mut();
+        if match (action, key) {
+            (Some(Action::Submit), _) => {
+                self.submit_guess();
+                true
+            }
+            (Some(Action::Hint), _) => self.show_hint(),
+            (Some(Action::Delete), _) => game.try_delete_letter(),
+            (Some(Action::Clear), _) => game.try_clear_letter(),
+            (Some(Action::Left), _) => game.try_move_cursor(CursorMove::Left),
+            (Some(Action::Right), _) => game.try_move_cursor(CursorMove::Right),
+            (Some(Action::Home), _) => game.try_move_cursor(CursorMove::Home),
+            (Some(Action::End), _) => game.try_move_cursor(CursorMove::End),
+            (Some(Action::NewGame), _) => {
+                if self.abandon_game(Words::todays_puzzle_number())? {
+                    self.start_game()?;
+                } else {
+                    self.view.set_message("Today's puzzle can't be skipped!");
+                }
+                return self.repaint();
+            }
+            (None, Key::Char(c)) => game.try_accept_letter(c),
             _ => false,
         } {
-            if let Some(won) = self.game.has_won() {
-                if won {
-                    self.game.set_message("You win!\nESC: quit, ENTER: new");
-                } else {
-                    self.game.set_message(&format!(
-                        "The word was '{}'.\nESC: quit, ENTER: new",
-                        self.game.answer()
-                    ));
+            if !self.options.animations {
+                self.view.skip_animation();
+            }
+
+            // The result is recorded straight away, but only shown once the
+            // last guess has been revealed.
+            if let Some(won) = self.game().has_won() {
+                self.record_result(won)?;
+                self.prepare_review();
+                if !self.view.is_animating() {
+                    self.show_result(won);
                 }
             }
             self.repaint()
@@ -62,23 +472,143 @@ impl<W: Write> App<W> {
         }
     }
 
+    /// Submit the letters typed into the current row. An accepted guess is
+    /// revealed a cell at a time. If it's rejected, the row is shaken and a
+    /// message says why, unless it just wasn't finished.
+    fn submit_guess(&mut self) {
+        let row = self.game().guesses_used();
+        let keyboard = self.game().keyboard().clone();
+        self.view.clear_message();
+        match self.game_mut().try_submit_guess() {
+            Ok(_) => self.view.reveal(row, keyboard),
+            Err(error) => {
+                if let Some(message) = rejection_message(&error) {
+                    self.view.set_message(&message);
+                }
+                self.view.shake();
+            }
+        }
+    }
+
+    /// Show the number of possible answers left and the guesses expected to
+    /// narrow them down the most. Returns whether there was a hint to show.
+    fn show_hint(&mut self) -> bool {
+        let Some(hint) = self.game().hint() else {
+            return false;
+        };
+        let message = match self.game().board_count() {
+            1 => hint_message(&hint),
+            _ => format!("Board {}: {}", hint.board + 1, hint_message(&hint)),
+        };
+        self.view.set_message(&message);
+        true
+    }
+
+    /// Whether an animation is playing, and `tick` should be called.
+    pub fn is_animating(&self) -> bool {
+        self.view.is_animating()
+    }
+
+    /// Advance the animation by a frame. Once it's over, show the result if
+    /// the game ended, or else handle the keys pressed in the meantime.
+    pub fn tick(&mut self) -> io::Result<()> {
+        if let State::Playing(game) = &self.state {
+            self.view.tick(game);
+        }
+        if !self.is_animating() {
+            let keys = mem::take(&mut self.queued_keys);
+            if let Some(won) = self.game().has_won() {
+                self.show_result(won);
+            } else {
+                for key in keys {
+                    self.handle_key(key)?;
+                }
+            }
+        }
+        self.repaint()
+    }
+
+    /// Show the statistics and a message about how the game ended.
+    fn show_result(&mut self, won: bool) {
+        self.show_stats();
+        if won {
+            self.set_game_over_message("You win!");
+        } else {
+            let missed = self.game().missed_answers();
+            let status = match &missed[..] {
+                [answer] => format!("The word was '{answer}'."),
+                _ => format!("The words were '{}'.", missed.join("', '")),
+            };
+            self.set_game_over_message(&status);
+        }
+    }
+
+    /// Switch to the next theme.
+    fn next_theme(&mut self) {
+        let custom = self.custom_colors.as_ref();
+        self.options.theme = self.options.theme.next(custom.is_some());
+        self.color_scheme = color_scheme_for(&self.options, custom);
+    }
+
+    /// Save an unfinished game so it can be resumed next time, or forget any
+    /// saved game if there's nothing worth keeping. A game saved with other
+    /// words is left alone.
+    pub fn save_progress(&self) -> io::Result<()> {
+        match (&self.storage, &self.state) {
+            _ if self.keeping_save => Ok(()),
+            (Some(storage), State::Playing(game)) if game.in_progress() => {
+                storage.save_game(&game.save())
+            }
+            (Some(storage), _) => storage.clear_game(),
+            (None, _) => Ok(()),
+        }
+    }
+
+    /// Show a status line followed by the keys available after a game. The
+    /// keys must fit on one line beneath the keyboard.
+    fn set_game_over_message(&mut self, status: &str) {
+        let message = format!(
+            "{status}\n{}: quit, {}: menu, {}/{}: copy/save",
+            self.options.keys.describe(Action::Quit),
+            self.options.keys.describe(Action::Submit),
+            self.options.keys.describe(Action::Copy),
+            self.options.keys.describe(Action::Save),
+        );
+        self.view.set_message(&message);
+    }
+
+    /// Copy the share text to the system clipboard using the OSC 52 escape
+    /// sequence, which the terminal forwards to the clipboard.
+    fn copy_share_text(&mut self) -> io::Result<()> {
+        let encoded =
+            util::base64_encode(self.game().share_text(self.color_scheme.squares).as_bytes());
+        write!(self.screen, "\x1b]52;c;{encoded}\x07")
+    }
+
     pub fn repaint(&mut self) -> io::Result<()> {
-        // Clear screen with appropriate background color.
-        write!(
-            self.screen,
-            "{}{}",
-            termion::color::Bg(self.color_scheme.game_bg),
-            termion::clear::All,
-        )?;
-
-        self.draw_board()?;
+        let size = match self.size {
+            Some(size) => size,
+            None => termion::terminal_size()?,
+        };
+        let mut frame = Frame::new(size, self.color_scheme.text_base, self.color_scheme.game_bg);
+        self.draw_board(&mut frame);
+        self.renderer.render(&frame, &mut self.screen)?;
         self.screen.flush()
     }
 
-    fn draw_board(&mut self) -> io::Result<()> {
-        let term_size = termion::terminal_size()?;
+    fn draw_board(&self, frame: &mut Frame) {
+        let term_size = frame.size();
+        let size = match &self.overlay {
+            Some(Overlay::Stats(stats)) => stats.size(),
+            Some(Overlay::Review(review)) => review.size(),
+            Some(Overlay::Help(help)) => help.size(),
+            None => match &self.state {
+                State::Menu(menu) => menu.size(),
+                State::Playing(game) => self.view.size(game, term_size),
+            },
+        };
 
-        if term_size.0 < Game::BOARD_SIZE.0 || term_size.1 < Game::BOARD_SIZE.1 {
+        if term_size.0 < size.0 || term_size.1 < size.1 {
             let resize_message = format!(
                 "[{}×{}] is too small! Please make your terminal window bigger.",
                 term_size.0, term_size.1
@@ -97,27 +627,283 @@ impl<W: Write> App<W> {
 
             for (i, line) in wrapped_message.into_iter().enumerate() {
                 let y_offset = i as u16;
-                write!(
-                    self.screen,
-                    "{}{}{}{}",
-                    termion::cursor::Goto(x, y + y_offset),
-                    termion::color::Bg(self.color_scheme.game_bg),
-                    termion::color::Fg(self.color_scheme.text_base),
-                    line,
-                )?;
+                frame.goto(x, y + y_offset).print(line);
             }
-            Ok(())
         } else {
-            let centered_top_left = util::get_centered_top_left(term_size, Game::BOARD_SIZE);
-            self.game
-                .paint(&mut self.screen, centered_top_left, &self.color_scheme)
+            let centered_top_left = util::get_centered_top_left(term_size, size);
+            match &self.overlay {
+                Some(Overlay::Stats(stats)) => {
+                    // Highlight the guess count of a game that was just won,
+                    // and offer to review a finished game.
+                    let highlight = match &self.state {
+                        State::Playing(game) => game
+                            .has_won()
+                            .and_then(|won| won.then(|| game.guesses_used())),
+                        State::Menu(_) => None,
+                    };
+                    let footer = if self.can_review() {
+                        format!(
+                            "{}: review, any other key: continue",
+                            self.options.keys.describe(Action::Review)
+                        )
+                    } else {
+                        "Press any key to continue".into()
+                    };
+                    stats.paint(
+                        frame,
+                        centered_top_left,
+                        &self.color_scheme,
+                        highlight,
+                        &footer,
+                    )
+                }
+                Some(Overlay::Review(review)) => {
+                    review.paint(frame, centered_top_left, &self.color_scheme)
+                }
+                Some(Overlay::Help(help)) => {
+                    help.paint(frame, centered_top_left, &self.color_scheme)
+                }
+                None => match &self.state {
+                    State::Menu(menu) => {
+                        menu.paint(frame, centered_top_left, &self.color_scheme, &self.options)
+                    }
+                    State::Playing(game) => self.view.paint(
+                        game,
+                        frame,
+                        centered_top_left,
+                        &self.color_scheme,
+                        term_size,
+                    ),
+                },
+            }
+        }
+    }
+}
+
+/// What to tell the player about a rejected guess, or `None` if it just
+/// wasn't finished.
+fn rejection_message(error: &GuessError) -> Option<String> {
+    Some(match error {
+        GuessError::Incomplete => return None,
+        GuessError::WrongLength => "Guess has the wrong number of letters".into(),
+        GuessError::NotInDictionary(guess) => format!("'{guess}' is not a valid word!"),
+        GuessError::HardModeViolation(HardModeViolation::Correct { position, letter }) => {
+            format!("{} letter must be {letter}", util::ordinal(position + 1))
+        }
+        GuessError::HardModeViolation(HardModeViolation::InWord { letter, count: 1 }) => {
+            format!("Guess must contain {letter}")
+        }
+        GuessError::HardModeViolation(HardModeViolation::InWord { letter, count }) => {
+            format!("Guess must contain {count} {letter}'s")
+        }
+        GuessError::GameOver => "The game is over".into(),
+        GuessError::AlreadyGuessed(guess) => format!("'{guess}' was already guessed!"),
+    })
+}
+
+/// Describe a hint: how many answers are possible, and the best guesses
+/// with the bits of information each is expected to give.
+fn hint_message(hint: &Hint) -> String {
+    match hint.possible[..] {
+        [] => "No possible answers left!".to_string(),
+        [answer] => format!("Only one possible answer: {}", answer.to_ascii_uppercase()),
+        _ => {
+            let suggestions = hint
+                .suggestions
+                .iter()
+                .map(|s| format!("{} {:.2}", s.word.to_ascii_uppercase(), s.bits))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "{} possible answers. Try:\n{suggestions}",
+                hint.possible.len()
+            )
         }
     }
 }
 
+/// The color scheme for the chosen theme, with shape cues if requested,
+/// reduced to the colors the terminal can show.
+fn color_scheme_for(options: &Options, custom: Option<&ColorScheme>) -> ColorScheme {
+    let mut colors = options.theme.colors(custom);
+    colors.cues |= options.cues;
+    colors.to_palette(options.palette.unwrap_or_else(Palette::detect))
+}
+
 impl<W: Write> Drop for App<W> {
     fn drop(&mut self) {
         // Reshow cursor on drop.
         write!(self.screen, "{}", termion::cursor::Show).unwrap();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::{assert_snapshot, Terminal};
+    use crate::TermionRenderer;
+
+    /// Start the app on `terminal` with no saved data and `crane` as the
+    /// only answer, and type `keys`.
+    fn start_app<'a>(
+        terminal: &'a mut Terminal,
+        options: Options,
+        keys: &str,
+    ) -> App<&'a mut Terminal> {
+        let options = Options {
+            palette: Some(Palette::Ansi256),
+            animations: false,
+            ..options
+        };
+        let words = Words::parse(5, Some("crane\n"), None).unwrap();
+        let renderer = Box::new(TermionRenderer);
+
+        let size = terminal.size();
+        let mut app = App::new(terminal, options, words, None, renderer, None).unwrap();
+        app.set_size(size);
+        app.repaint().unwrap();
+        for c in keys.chars() {
+            app.handle_key(Key::Char(c)).unwrap();
+        }
+        app
+    }
+
+    /// Start the app on a terminal of `size`, type `keys` and compare the
+    /// screen left to the named snapshot.
+    fn assert_screen(name: &str, size: (u16, u16), keys: &str) {
+        let mut terminal = Terminal::new(size);
+        drop(start_app(&mut terminal, Options::default(), keys));
+        assert_snapshot(name, &terminal.screen());
+    }
+
+    #[test]
+    fn draws_screens() {
+        assert_screen("menu", (80, 24), "");
+        assert_screen("help", (80, 30), "\n?");
+        assert_screen("menu-help", (80, 24), "?");
+        // A scored guess, a rejected one, and the keyboard showing both.
+        assert_screen("game", (80, 30), "\nslate\nzzzzz\n");
+        assert_screen("won-stats", (80, 30), "\ncrane\n");
+        assert_screen("won", (80, 30), "\ncrane\n ");
+        assert_screen("too-small", (30, 10), "");
+    }
+
+    #[test]
+    fn abandons_games() {
+        // Starting another game part way through counts as a loss.
+        let mut terminal = Terminal::new((80, 30));
+        let mut app = start_app(&mut terminal, Options::default(), "\nslate\n");
+        app.handle_key(Key::Ctrl('n')).unwrap();
+        assert_eq!(app.records.len(), 1);
+        assert!(!app.records[0].won);
+        assert_eq!(app.game().guesses_used(), 0);
+
+        // But not before any guesses are made.
+        app.handle_key(Key::Ctrl('n')).unwrap();
+        assert_eq!(app.records.len(), 1);
+
+        // Today's puzzle can't be skipped.
+        let options = Options {
+            mode: Mode::Daily,
+            ..Default::default()
+        };
+        let mut terminal = Terminal::new((80, 30));
+        let mut app = start_app(&mut terminal, options.clone(), "\nslate\n");
+        app.handle_key(Key::Ctrl('n')).unwrap();
+        assert!(app.records.is_empty());
+        assert_eq!(app.game().guesses_used(), 1);
+        drop(app);
+        assert!(terminal
+            .screen()
+            .text()
+            .contains("Today's puzzle can't be skipped!"));
+
+        // Once the day is over, it's given up like any other game.
+        let mut terminal = Terminal::new((80, 30));
+        let mut app = start_app(&mut terminal, options, "\nslate\n");
+        let today = Words::todays_puzzle_number();
+        assert!(!app.abandon_game(today).unwrap());
+        assert!(app.abandon_game(today + 1).unwrap());
+        assert_eq!(app.records.len(), 1);
+    }
+
+    #[test]
+    fn looks_up_prompt_keys() {
+        // Letters bound to prompt actions are still typed during a game.
+        let mut options = Options::default();
+        options.keys.bind("review", "v").unwrap();
+        let mut terminal = Terminal::new((80, 30));
+        let mut app = start_app(&mut terminal, options, "\nrav");
+        assert!(app.overlay.is_none());
+        let row = &app.game().boards()[0].rows()[0];
+        assert_eq!(row.cells()[2].get_char().to_ascii_lowercase(), 'v');
+
+        // And pick the action once it's asked for.
+        for _ in 0..3 {
+            app.handle_key(Key::Backspace).unwrap();
+        }
+        for c in "crane\n".chars() {
+            app.handle_key(Key::Char(c)).unwrap();
+        }
+        assert!(matches!(app.overlay, Some(Overlay::Stats(_))));
+        app.handle_key(Key::Char('v')).unwrap();
+        assert!(matches!(app.overlay, Some(Overlay::Review(_))));
+    }
+
+    #[test]
+    fn describes_rejected_guesses() {
+        let describe = |error| rejection_message(&error);
+        assert_eq!(describe(GuessError::Incomplete), None);
+        assert_eq!(
+            describe(GuessError::NotInDictionary("xxxxx".into())).as_deref(),
+            Some("'xxxxx' is not a valid word!")
+        );
+
+        let violation = |violation| describe(GuessError::HardModeViolation(violation));
+        assert_eq!(
+            violation(HardModeViolation::Correct {
+                position: 0,
+                letter: 'H'
+            })
+            .as_deref(),
+            Some("1st letter must be H")
+        );
+        assert_eq!(
+            violation(HardModeViolation::InWord {
+                letter: 'H',
+                count: 1
+            })
+            .as_deref(),
+            Some("Guess must contain H")
+        );
+        assert_eq!(
+            violation(HardModeViolation::InWord {
+                letter: 'C',
+                count: 2
+            })
+            .as_deref(),
+            Some("Guess must contain 2 C's")
+        );
+    }
+
+    #[test]
+    fn describes_hints() {
+        // None of the answers share a letter with the guess, so all of them
+        // are still possible after it.
+        let words = Words::parse(5, Some("crate\ntrace\nreact\ncater\nheart\n"), None).unwrap();
+        let mut game = Game::new(&Default::default(), Rc::new(words));
+        game.guess("sound").unwrap();
+
+        let message = hint_message(&game.hint().unwrap());
+        let (count, suggestions) = message.split_once(" possible answers. Try:\n").unwrap();
+        assert_eq!(count, "5");
+        assert_eq!(suggestions.split(", ").count(), 3);
+
+        let hint = Hint {
+            board: 0,
+            possible: vec!["heart"],
+            suggestions: Vec::new(),
+        };
+        assert_eq!(hint_message(&hint), "Only one possible answer: HEART");
+    }
+}
@@ -1,23 +1,459 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::util;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct ColorScheme {
-    pub game_bg: termion::color::Rgb,
-    pub cell_base: termion::color::Rgb,
-    pub cell_row_active: termion::color::Rgb,
-    pub cell_active: termion::color::Rgb,
-    pub cell_in_word: termion::color::Rgb,
-    pub cell_correct: termion::color::Rgb,
-    pub text_base: termion::color::Rgb,
-    pub text_inverted: termion::color::Rgb,
+    pub game_bg: Color,
+    pub cell_base: Color,
+    pub cell_row_active: Color,
+    pub cell_active: Color,
+    pub cell_in_word: Color,
+    pub cell_correct: Color,
+    pub text_base: Color,
+    pub text_dim: Color,
+    pub text_inverted: Color,
+    /// Mark cells and keys that are in the word or correct with shapes in
+    /// their borders, so they can be told apart without color.
+    pub cues: bool,
+    /// Emoji squares for the share text: for letters not in the word, in
+    /// the word, and correct.
+    pub squares: [char; 3],
+}
+
+/// A choice of color scheme.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Theme {
+    Latte,
+    Frappe,
+    Macchiato,
+    #[default]
+    Mocha,
+    /// Orange and blue instead of green and yellow, with shape cues.
+    HighContrast,
+    /// A color scheme loaded from a file.
+    Custom,
+}
+
+/// How many colors the terminal can show.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Palette {
+    /// Any 24-bit color.
+    #[default]
+    TrueColor,
+    /// The xterm 256-color palette.
+    Ansi256,
+    /// The 16 basic ANSI colors.
+    Ansi16,
+    /// No colors: bold, underline and reverse video only.
+    Mono,
+}
+
+impl Palette {
+    /// Get a palette by name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "truecolor" | "24bit" => Some(Self::TrueColor),
+            "256" => Some(Self::Ansi256),
+            "16" => Some(Self::Ansi16),
+            "mono" => Some(Self::Mono),
+            _ => None,
+        }
+    }
+
+    /// Guess what the terminal supports from the environment.
+    pub fn detect() -> Self {
+        Self::from_env(
+            env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()),
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    /// Guess the palette from whether `NO_COLOR` is set, and the values of
+    /// `COLORTERM` and `TERM`.
+    fn from_env(no_color: bool, colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if no_color {
+            return Self::Mono;
+        }
+        if let Some("truecolor" | "24bit") = colorterm {
+            return Self::TrueColor;
+        }
+        match term {
+            None | Some("dumb") => Self::Mono,
+            // Serial terminals such as the VT100 have no colors at all.
+            Some(term) if term.starts_with("vt") => Self::Mono,
+            Some(term) if term.contains("truecolor") || term.contains("direct") => Self::TrueColor,
+            Some(term) if term.contains("256color") => Self::Ansi256,
+            Some(_) => Self::Ansi16,
+        }
+    }
+}
+
+/// A 24-bit color.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+/// A color as written to the terminal, reduced to what it can show.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Color {
+    Rgb(Rgb),
+    /// An index into the 256-color palette.
+    Ansi256(u8),
+    /// One of the 16 basic colors, with 8 to 15 being the bright ones.
+    Ansi16(u8),
+    /// Text attributes standing in for a color, as SGR parameters such as
+    /// `1;7`: one set for text drawn in the color and one for text drawn on
+    /// it. Drawing on it first clears any previous attributes.
+    Mono {
+        fg: &'static str,
+        bg: &'static str,
+    },
+}
+
+impl Color {
+    /// Reduce a 24-bit color to the closest one in a palette. Monochrome
+    /// colors depend on what they're used for, so they are left alone here.
+    fn to_palette(self, palette: Palette) -> Self {
+        let Self::Rgb(rgb) = self else {
+            return self;
+        };
+        match palette {
+            Palette::TrueColor | Palette::Mono => self,
+            Palette::Ansi256 => Self::Ansi256(nearest_ansi256(rgb)),
+            Palette::Ansi16 => Self::Ansi16(nearest_ansi16(rgb)),
+        }
+    }
+}
+
+impl termion::color::Color for Color {
+    fn write_fg(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Rgb(Rgb(r, g, b)) => write!(f, "\x1b[38;2;{r};{g};{b}m"),
+            Self::Ansi256(n) => f.write_str(&termion::color::AnsiValue(n).fg_string()),
+            Self::Ansi16(n @ 0..=7) => write!(f, "\x1b[{}m", 30 + n),
+            Self::Ansi16(n) => write!(f, "\x1b[{}m", 90 + (n & 7)),
+            Self::Mono { fg: "", .. } => Ok(()),
+            Self::Mono { fg, .. } => write!(f, "\x1b[{fg}m"),
+        }
+    }
+
+    fn write_bg(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Rgb(Rgb(r, g, b)) => write!(f, "\x1b[48;2;{r};{g};{b}m"),
+            Self::Ansi256(n) => f.write_str(&termion::color::AnsiValue(n).bg_string()),
+            Self::Ansi16(n @ 0..=7) => write!(f, "\x1b[{}m", 40 + n),
+            Self::Ansi16(n) => write!(f, "\x1b[{}m", 100 + (n & 7)),
+            Self::Mono { bg: "", .. } => write!(f, "\x1b[0m"),
+            Self::Mono { bg, .. } => write!(f, "\x1b[0;{bg}m"),
+        }
+    }
+}
+
+/// The closest color in the 256-color palette: either in the 6×6×6 color
+/// cube or on the 24-step gray ramp.
+fn nearest_ansi256(Rgb(r, g, b): Rgb) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        [(r, r2), (g, g2), (b, b2)]
+            .iter()
+            .map(|&(a, b)| (a as i32 - b as i32).pow(2))
+            .sum::<i32>()
+    };
+    let level = |c: u8| {
+        (0..LEVELS.len())
+            .min_by_key(|&i| (LEVELS[i] as i32 - c as i32).abs())
+            .expect("Levels are not empty")
+    };
+
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (LEVELS[ri], LEVELS[gi], LEVELS[bi]);
+    let gray_step = ((r as u32 + g as u32 + b as u32) / 3).saturating_sub(3) / 10;
+    let gray_step = gray_step.min(23) as u8;
+    let gray = 8 + gray_step * 10;
+
+    if distance((gray, gray, gray)) < distance(cube) {
+        232 + gray_step
+    } else {
+        16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8
+    }
+}
+
+/// The closest of the 16 basic colors. Their exact shades vary between
+/// terminals, so this keeps the hue and rough brightness rather than
+/// measuring distance: pale colors would otherwise all turn white.
+fn nearest_ansi16(Rgb(r, g, b): Rgb) -> u8 {
+    let max = r.max(g).max(b) as u16;
+    let min = r.min(g).min(b) as u16;
+
+    if max - min < 48 {
+        return match (max + min) / 2 {
+            0..=63 => 0,
+            64..=159 => 8,
+            160..=223 => 7,
+            _ => 15,
+        };
+    }
+
+    let mid = (max + min) / 2;
+    let on = |c: u8| (c as u16 > mid) as u8;
+    let color = on(r) | on(g) << 1 | on(b) << 2;
+    if max >= 192 {
+        color + 8
+    } else {
+        color
+    }
+}
+
+impl Theme {
+    /// Themes in the order they are switched through.
+    const ALL: [Self; 6] = [
+        Self::Latte,
+        Self::Frappe,
+        Self::Macchiato,
+        Self::Mocha,
+        Self::HighContrast,
+        Self::Custom,
+    ];
+
+    /// Get a built-in theme by name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "latte" => Some(Self::Latte),
+            "frappe" | "frappé" => Some(Self::Frappe),
+            "macchiato" => Some(Self::Macchiato),
+            "mocha" => Some(Self::Mocha),
+            "high-contrast" => Some(Self::HighContrast),
+            _ => None,
+        }
+    }
+
+    /// The name of a theme, as accepted by `from_name` for all but the
+    /// custom theme.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Latte => "latte",
+            Self::Frappe => "frappe",
+            Self::Macchiato => "macchiato",
+            Self::Mocha => "mocha",
+            Self::HighContrast => "high-contrast",
+            Self::Custom => "custom",
+        }
+    }
+
+    /// The theme after this one, skipping the custom theme if there isn't
+    /// one.
+    pub fn next(self, has_custom: bool) -> Self {
+        self.step(true, has_custom)
+    }
+
+    /// The theme before this one, skipping the custom theme if there isn't
+    /// one.
+    pub fn previous(self, has_custom: bool) -> Self {
+        self.step(false, has_custom)
+    }
+
+    /// The theme after or before this one, wrapping around.
+    fn step(self, forward: bool, has_custom: bool) -> Self {
+        let themes: Vec<Self> = Self::ALL
+            .into_iter()
+            .filter(|&t| t != Self::Custom || has_custom)
+            .collect();
+        let n = themes.len();
+        match themes.iter().position(|&t| t == self) {
+            Some(i) if forward => themes[(i + 1) % n],
+            Some(i) => themes[(i + n - 1) % n],
+            None => themes[0],
+        }
+    }
+
+    /// Get the color scheme for this theme, given the custom scheme loaded
+    /// from a file, if any. Falls back to mocha if the custom theme is
+    /// chosen without one.
+    pub fn colors(self, custom: Option<&ColorScheme>) -> ColorScheme {
+        let flavor = match self {
+            Self::Latte => catppuccin::PALETTE.latte,
+            Self::Frappe => catppuccin::PALETTE.frappe,
+            Self::Macchiato => catppuccin::PALETTE.macchiato,
+            Self::Mocha => catppuccin::PALETTE.mocha,
+            Self::HighContrast => return ColorScheme::HIGH_CONTRAST,
+            Self::Custom => match custom {
+                Some(colors) => return *colors,
+                None => catppuccin::PALETTE.mocha,
+            },
+        };
+        ColorScheme::from(flavor)
+    }
+}
+
+impl ColorScheme {
+    /// Black and white with orange and blue hints, which can be told apart
+    /// with the most common kinds of color blindness.
+    const HIGH_CONTRAST: Self = Self {
+        game_bg: Color::Rgb(Rgb(0, 0, 0)),
+        cell_base: Color::Rgb(Rgb(58, 58, 60)),
+        cell_row_active: Color::Rgb(Rgb(86, 87, 88)),
+        cell_active: Color::Rgb(Rgb(129, 131, 132)),
+        cell_in_word: Color::Rgb(Rgb(133, 192, 249)),
+        cell_correct: Color::Rgb(Rgb(245, 121, 58)),
+        text_base: Color::Rgb(Rgb(255, 255, 255)),
+        text_dim: Color::Rgb(Rgb(129, 131, 132)),
+        text_inverted: Color::Rgb(Rgb(0, 0, 0)),
+        cues: true,
+        squares: ['⬛', '🟦', '🟧'],
+    };
+
+    /// Stand-ins for each color in monochrome, chosen so that hints and the
+    /// board still stand out: cells are drawn in reverse video, underlined
+    /// if the letter is in the word and bold if it's correct, and the cell
+    /// being typed in is only underlined. Shape cues are always drawn.
+    const MONO: Self = Self {
+        game_bg: Color::Mono { fg: "", bg: "" },
+        cell_base: Color::Mono { fg: "", bg: "7" },
+        cell_row_active: Color::Mono { fg: "", bg: "7" },
+        cell_active: Color::Mono { fg: "", bg: "4" },
+        cell_in_word: Color::Mono { fg: "", bg: "4;7" },
+        cell_correct: Color::Mono { fg: "", bg: "1;7" },
+        text_base: Color::Mono { fg: "", bg: "" },
+        text_dim: Color::Mono { fg: "2", bg: "" },
+        text_inverted: Color::Mono { fg: "", bg: "" },
+        cues: true,
+        squares: ['⬛', '🟨', '🟩'],
+    };
+
+    /// Reduce the colors of this scheme to what a palette can show.
+    pub fn to_palette(self, palette: Palette) -> Self {
+        if palette == Palette::Mono {
+            return Self {
+                squares: self.squares,
+                ..Self::MONO
+            };
+        }
+
+        let c = |color: Color| color.to_palette(palette);
+        Self {
+            game_bg: c(self.game_bg),
+            cell_base: c(self.cell_base),
+            cell_row_active: c(self.cell_row_active),
+            cell_active: c(self.cell_active),
+            cell_in_word: c(self.cell_in_word),
+            cell_correct: c(self.cell_correct),
+            text_base: c(self.text_base),
+            text_dim: c(self.text_dim),
+            text_inverted: c(self.text_inverted),
+            ..self
+        }
+    }
+
+    /// Names of the color fields, as used in color scheme files.
+    const FIELDS: [&'static str; 9] = [
+        "game_bg",
+        "cell_base",
+        "cell_row_active",
+        "cell_active",
+        "cell_in_word",
+        "cell_correct",
+        "text_base",
+        "text_dim",
+        "text_inverted",
+    ];
+
+    /// Load a color scheme from a file (see `parse`).
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+        Self::parse(&path.display().to_string(), &text)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Parse a color scheme made of `field = "#rrggbb"` lines, one for each
+    /// color field. `cues` and `squares` may also be given, and default to
+    /// no cues and the usual squares. Errors are reported as coming from
+    /// `source`.
+    fn parse(source: &str, text: &str) -> Result<Self, String> {
+        let mut colors = [None; Self::FIELDS.len()];
+        let mut cues = false;
+        let mut squares = ['⬛', '🟨', '🟩'];
+
+        for (line, entry) in util::config_entries(text) {
+            let result = entry.and_then(|(key, value)| {
+                match key {
+                    "cues" => {
+                        cues = util::parse_bool(value)?;
+                        return Ok(());
+                    }
+                    "squares" => {
+                        let chars: Vec<_> = value.chars().collect();
+                        squares = chars.try_into().map_err(|_| {
+                            format!("expected three squares like '⬛🟨🟩', not '{value}'")
+                        })?;
+                        return Ok(());
+                    }
+                    _ => {}
+                }
+
+                let i = Self::FIELDS
+                    .iter()
+                    .position(|&field| field == key)
+                    .ok_or_else(|| format!("unknown color '{key}'"))?;
+                colors[i] = Some(Color::Rgb(parse_hex(value)?));
+                Ok(())
+            });
+            result.map_err(|e| format!("{source}:{line}: {e}"))?;
+        }
+
+        let missing: Vec<_> = Self::FIELDS
+            .iter()
+            .zip(&colors)
+            .filter(|(_, color)| color.is_none())
+            .map(|(&field, _)| field)
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!("{source}: missing colors: {}", missing.join(", ")));
+        }
+
+        let [game_bg, cell_base, cell_row_active, cell_active, cell_in_word, cell_correct, text_base, text_dim, text_inverted] =
+            colors.map(Option::unwrap);
+        Ok(Self {
+            game_bg,
+            cell_base,
+            cell_row_active,
+            cell_active,
+            cell_in_word,
+            cell_correct,
+            text_base,
+            text_dim,
+            text_inverted,
+            cues,
+            squares,
+        })
+    }
 }
 
-trait IntoTermionRgb {
-    fn to_termion_rgb(&self) -> termion::color::Rgb;
+/// Parse a hex color such as `#1e1e2e`. The `#` is optional.
+fn parse_hex(value: &str) -> Result<Rgb, String> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("expected a color like '#1e1e2e', not '{value}'"));
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).expect("Checked above");
+    Ok(Rgb(channel(0), channel(2), channel(4)))
+}
+
+trait IntoColor {
+    fn to_color(&self) -> Color;
 }
 
 // Default color scheme is built with catppuccin :)
-impl IntoTermionRgb for catppuccin::Color {
-    fn to_termion_rgb(&self) -> termion::color::Rgb {
+impl IntoColor for catppuccin::Color {
+    fn to_color(&self) -> Color {
         let catppuccin::Rgb { r, g, b } = self.rgb;
-        termion::color::Rgb(r, g, b)
+        Color::Rgb(Rgb(r, g, b))
     }
 }
 
@@ -25,14 +461,117 @@ impl From<catppuccin::Flavor> for ColorScheme {
     fn from(value: catppuccin::Flavor) -> Self {
         let c = value.colors;
         Self {
-            game_bg: c.base.to_termion_rgb(),
-            cell_base: c.surface1.to_termion_rgb(),
-            cell_row_active: c.surface2.to_termion_rgb(),
-            cell_active: c.overlay1.to_termion_rgb(),
-            cell_in_word: c.yellow.to_termion_rgb(),
-            cell_correct: c.green.to_termion_rgb(),
-            text_base: c.text.to_termion_rgb(),
-            text_inverted: c.base.to_termion_rgb(),
+            game_bg: c.base.to_color(),
+            cell_base: c.surface1.to_color(),
+            cell_row_active: c.surface2.to_color(),
+            cell_active: c.overlay1.to_color(),
+            cell_in_word: c.yellow.to_color(),
+            cell_correct: c.green.to_color(),
+            text_base: c.text.to_color(),
+            text_dim: c.overlay0.to_color(),
+            text_inverted: c.base.to_color(),
+            cues: false,
+            squares: [if value.dark { '⬛' } else { '⬜' }, '🟨', '🟩'],
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_color_schemes() {
+        let mocha = ColorScheme::from(catppuccin::PALETTE.mocha);
+        let text: String = ColorScheme::FIELDS
+            .iter()
+            .map(|field| format!("{field} = \"#1e1e2e\"\n"))
+            .collect();
+        let parse = |text: &str| ColorScheme::parse("colors.toml", text);
+
+        let colors = parse(&format!("# Dark\n{text}")).unwrap();
+        assert_eq!(colors.game_bg, mocha.game_bg);
+        assert_eq!(colors.text_inverted, Color::Rgb(Rgb(0x1e, 0x1e, 0x2e)));
+        assert_eq!((colors.cues, colors.squares), (false, mocha.squares));
+
+        let colors = parse(&format!("{text}cues = true\nsquares = \"⬛🟦🟧\"")).unwrap();
+        assert_eq!((colors.cues, colors.squares), (true, ['⬛', '🟦', '🟧']));
+        assert!(parse(&format!("{text}squares = \"⬛🟦\"")).is_err());
+
+        assert_eq!(
+            parse("game_bg = \"#1e1e2\"").unwrap_err(),
+            "colors.toml:1: expected a color like '#1e1e2e', not '#1e1e2'"
+        );
+        assert_eq!(
+            parse("\ngame_fg = ffffff").unwrap_err(),
+            "colors.toml:2: unknown color 'game_fg'"
+        );
+        assert_eq!(
+            parse(&text.replace("text_dim", "# text_dim")).unwrap_err(),
+            "colors.toml: missing colors: text_dim"
+        );
+    }
+
+    #[test]
+    fn detects_palettes() {
+        let detect = |no_color, colorterm, term| Palette::from_env(no_color, colorterm, term);
+        assert_eq!(
+            detect(false, Some("truecolor"), Some("xterm")),
+            Palette::TrueColor
+        );
+        assert_eq!(detect(false, None, Some("tmux-256color")), Palette::Ansi256);
+        assert_eq!(
+            detect(false, None, Some("xterm-direct")),
+            Palette::TrueColor
+        );
+        assert_eq!(detect(false, None, Some("linux")), Palette::Ansi16);
+        assert_eq!(detect(false, None, Some("vt220")), Palette::Mono);
+        assert_eq!(detect(false, None, None), Palette::Mono);
+        assert_eq!(
+            detect(true, Some("truecolor"), Some("xterm")),
+            Palette::Mono
+        );
+    }
+
+    #[test]
+    fn reduces_colors() {
+        assert_eq!(nearest_ansi256(Rgb(0, 0, 0)), 16);
+        assert_eq!(nearest_ansi256(Rgb(255, 0, 0)), 196);
+        assert_eq!(nearest_ansi256(Rgb(128, 128, 128)), 244);
+
+        // Mocha keeps its hues, even the pale ones.
+        let mocha = Theme::Mocha.colors(None).to_palette(Palette::Ansi16);
+        assert_eq!(mocha.game_bg, Color::Ansi16(0));
+        assert_eq!(mocha.cell_base, Color::Ansi16(8));
+        assert_eq!(mocha.cell_in_word, Color::Ansi16(11));
+        assert_eq!(mocha.cell_correct, Color::Ansi16(10));
+        assert_eq!(mocha.text_base, Color::Ansi16(15));
+
+        let mono = Theme::Latte.colors(None).to_palette(Palette::Mono);
+        assert!(mono.cues);
+        assert_eq!(mono.squares, Theme::Latte.colors(None).squares);
+        assert_eq!(
+            format!("{}", termion::color::Bg(mono.cell_correct)),
+            "\x1b[0;1;7m"
+        );
+    }
+
+    #[test]
+    fn switches_themes() {
+        assert_eq!(Theme::from_name("frappé"), Some(Theme::Frappe));
+        assert_eq!(Theme::from_name("custom"), None);
+
+        assert_eq!(Theme::Latte.next(false), Theme::Frappe);
+        assert_eq!(Theme::Mocha.next(false), Theme::HighContrast);
+        assert_eq!(Theme::HighContrast.next(false), Theme::Latte);
+        assert_eq!(Theme::HighContrast.next(true), Theme::Custom);
+        assert_eq!(Theme::Custom.next(true), Theme::Latte);
+        assert_eq!(Theme::Latte.previous(false), Theme::HighContrast);
+        assert_eq!(Theme::Latte.previous(true), Theme::Custom);
+        assert_eq!(Theme::Frappe.previous(true), Theme::Latte);
+
+        let custom = Theme::Latte.colors(None);
+        assert_eq!(Theme::Custom.colors(Some(&custom)), custom);
+        assert_eq!(Theme::Custom.colors(None), Theme::Mocha.colors(None));
+    }
+}
@@ -0,0 +1,232 @@
+//! Scoring guesses against answers.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Words;
+
+/// What a guess reveals about one of its letters.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Mark {
+    /// The letter is not in the word (or not as many times as guessed).
+    Absent = 0,
+    /// The letter is in the word, in another spot.
+    Present = 1,
+    /// The letter is in the right spot.
+    Correct = 2,
+}
+
+impl Mark {
+    /// The character standing for this mark in a written pattern.
+    pub fn to_char(self) -> char {
+        match self {
+            Self::Absent => '.',
+            Self::Present => 'Y',
+            Self::Correct => 'G',
+        }
+    }
+
+    /// Get a mark from its character, in either case.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            '.' => Some(Self::Absent),
+            'Y' => Some(Self::Present),
+            'G' => Some(Self::Correct),
+            _ => None,
+        }
+    }
+
+    fn digit(self) -> u32 {
+        self as u32
+    }
+}
+
+/// The marks a guess gets against an answer, one per letter.
+///
+/// Stored as a base-3 number with one digit per letter, first letter in the
+/// lowest digit: 2 if the letter is correct, 1 if it is elsewhere in the
+/// word, and 0 if it is not in the word. This makes it cheap to compare and
+/// to count by `index`. It's written as a string like `"GY..G"`.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Feedback {
+    pattern: u32,
+    length: u8,
+}
+
+impl Feedback {
+    /// Number of distinct patterns for words of `length` letters.
+    pub fn count(length: usize) -> usize {
+        3usize.pow(length as u32)
+    }
+
+    /// Get a pattern from its index, as given by `index`.
+    ///
+    /// # Panics
+    /// Panics if `index` is not less than `count(length)`.
+    pub fn from_index(index: usize, length: usize) -> Self {
+        assert!(index < Self::count(length), "pattern index out of range");
+        Self {
+            pattern: index as u32,
+            length: length as u8,
+        }
+    }
+
+    /// A number below `count(self.length())` identifying this pattern among
+    /// those of the same length, for counting patterns in a table.
+    pub fn index(self) -> usize {
+        self.pattern as usize
+    }
+
+    /// Number of letters marked.
+    pub fn length(self) -> usize {
+        self.length as usize
+    }
+
+    /// Whether every letter is correct.
+    pub fn is_solved(self) -> bool {
+        self.index() == Self::count(self.length()) - 1
+    }
+
+    /// The mark given to each letter, in order.
+    pub fn marks(self) -> impl Iterator<Item = Mark> {
+        let mut pattern = self.pattern;
+        (0..self.length).map(move |_| {
+            let mark = match pattern % 3 {
+                2 => Mark::Correct,
+                1 => Mark::Present,
+                _ => Mark::Absent,
+            };
+            pattern /= 3;
+            mark
+        })
+    }
+}
+
+impl FromIterator<Mark> for Feedback {
+    /// # Panics
+    /// Panics if there are more than `Words::MAX_LENGTH` marks.
+    fn from_iter<I: IntoIterator<Item = Mark>>(marks: I) -> Self {
+        let marks: Vec<_> = marks.into_iter().collect();
+        assert!(marks.len() <= Words::MAX_LENGTH, "too many marks");
+        Self {
+            pattern: marks
+                .iter()
+                .rev()
+                .fold(0, |pattern, mark| pattern * 3 + mark.digit()),
+            length: marks.len() as u8,
+        }
+    }
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.marks()
+            .try_for_each(|mark| write!(f, "{}", mark.to_char()))
+    }
+}
+
+impl FromStr for Feedback {
+    type Err = String;
+
+    /// Parse a pattern written with `G` for correct letters, `Y` for letters
+    /// in the word and `.` for letters not in the word.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.chars().count() > Words::MAX_LENGTH {
+            return Err(format!(
+                "expected at most {} marks, not '{s}'",
+                Words::MAX_LENGTH
+            ));
+        }
+        s.chars()
+            .map(|c| Mark::from_char(c).ok_or_else(|| format!("expected G, Y or ., not '{c}'")))
+            .collect()
+    }
+}
+
+/// Score a guess against an answer of the same length.
+///
+/// This is the "meat" of the wordle logic. Exact matches are found first,
+/// then repeated letters are marked as in the word from left to right, only
+/// as many times as the answer has "un-greened" instances of that letter.
+///
+/// # Panics
+/// Panics if the words are longer than `Words::MAX_LENGTH` or contain
+/// characters other than ASCII letters.
+pub fn score(guess: &str, answer: &str) -> Feedback {
+    let letter_index = |b: u8| (b.to_ascii_lowercase() - b'a') as usize;
+    let mut marks = [Mark::Absent; Words::MAX_LENGTH];
+    let mut unmatched = [0u8; 26];
+
+    // Start with exact matches, counting the answer's other letters.
+    for (i, (g, a)) in guess.bytes().zip(answer.bytes()).enumerate() {
+        if g.eq_ignore_ascii_case(&a) {
+            marks[i] = Mark::Correct;
+        } else {
+            unmatched[letter_index(a)] += 1;
+        }
+    }
+
+    // Fill in yellows from left to right while unmatched letters remain.
+    for (i, g) in guess.bytes().enumerate() {
+        let count = &mut unmatched[letter_index(g)];
+        if marks[i] != Mark::Correct && *count > 0 {
+            *count -= 1;
+            marks[i] = Mark::Present;
+        }
+    }
+
+    marks[..guess.len()].iter().copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(s: &str) -> Feedback {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn scores_patterns() {
+        assert_eq!(score("heart", "heart"), pattern("GGGGG"));
+        assert_eq!(score("sound", "heart"), pattern("....."));
+        assert_eq!(score("earth", "heart"), pattern("YYYYY"));
+        assert_eq!(score("gucci", "cacti"), pattern("..GYG"));
+        assert_eq!(score("speed", "abide"), pattern("..Y.Y"));
+        assert_eq!(score("LEVEL", "hello"), pattern("YG..Y"));
+        assert!(score("heart", "heart").is_solved());
+        assert!(!score("earth", "heart").is_solved());
+    }
+
+    #[test]
+    fn converts_patterns() {
+        let feedback = pattern("gy..G");
+        assert_eq!(feedback.to_string(), "GY..G");
+        assert_eq!(feedback.length(), 5);
+        assert_eq!(
+            feedback.marks().collect::<Vec<_>>(),
+            [
+                Mark::Correct,
+                Mark::Present,
+                Mark::Absent,
+                Mark::Absent,
+                Mark::Correct
+            ]
+        );
+
+        // Patterns of different lengths are told apart, even when every
+        // letter is absent.
+        assert_ne!(pattern("..."), pattern("...."));
+        assert_eq!(pattern("").to_string(), "");
+
+        let index = feedback.index();
+        assert!(index < Feedback::count(5));
+        assert_eq!(Feedback::from_index(index, 5), feedback);
+
+        assert_eq!(
+            "GX".parse::<Feedback>(),
+            Err("expected G, Y or ., not 'X'".into())
+        );
+        assert!("G".repeat(12).parse::<Feedback>().is_err());
+    }
+}
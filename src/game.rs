@@ -1,165 +1,892 @@
-use std::collections::HashMap;
-use std::io::{self, Write};
+//! The state of a game and the rules for playing it.
 
-use crate::{ColorScheme, Words};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
 
-/// Game state.
+use crate::solver::{self, Suggestion};
+use crate::{score, Feedback, Mark, Mode, Settings, Words};
+
+/// Game state: one or more boards, and the keyboard showing what is known
+/// about each letter.
+///
+/// Letters can be typed into the current row one at a time, as a player
+/// would, and submitted with `try_submit_guess`; or a whole word can be
+/// played with `guess`.
 pub struct Game {
-    rows: [BoardRow; 6],
+    boards: Vec<Board>,
     current_row: usize,
-    answer: &'static str,
-    words: Words,
-    display_message: Option<String>,
+    keyboard: Keyboard,
+    puzzle_number: Option<u32>,
+    words: Rc<Words>,
+    hard_mode: bool,
     has_won: Option<bool>,
 }
 
 impl Game {
-    /// The size (w, h) of the wordle board drawn with characters. Includes
-    /// two extra rows at the bottom for a message.
-    pub const BOARD_SIZE: (u16, u16) = (Cell::SIZE.0 * 5, Cell::SIZE.1 * 6 + 2);
+    /// First line of a saved game, which changes if the format does.
+    const SAVE_HEADER: &'static str = "wordlers save 3";
 
-    pub fn new() -> Self {
-        let words = Words::new();
-        let answer = words.get_answer();
+    /// Start a game with answers chosen from `words` as `settings` says.
+    pub fn new(settings: &Settings, words: Rc<Words>) -> Self {
+        let (answers, puzzle_number) = match settings.mode {
+            // In Absurdle mode, these are only stand-ins until the first guess.
+            Mode::Random | Mode::Absurdle => (words.get_answers(settings.boards), None),
+            Mode::Daily => {
+                // Every board of a daily puzzle gets a different answer.
+                let n = Words::todays_puzzle_number();
+                let boards = settings.boards as u32;
+                let answers = (0..boards)
+                    .map(|i| words.get_daily_answer(n * boards + i))
+                    .collect();
+                (answers, Some(n))
+            }
+        };
 
+        let candidates = (settings.mode == Mode::Absurdle).then(|| words.answers().to_vec());
         let mut game = Self {
-            rows: [BoardRow::empty(); 6],
+            boards: answers
+                .into_iter()
+                .map(|answer| Board::new(answer, settings.rows, candidates.clone()))
+                .collect(),
             current_row: 0,
-            answer,
+            keyboard: Keyboard::default(),
+            puzzle_number,
             words,
-            display_message: None,
+            hard_mode: settings.hard_mode,
             has_won: None,
         };
 
         // Initialize game state.
-        game.rows[0].current_cell = Some(0);
+        for row in game.active_rows() {
+            row.current_cell = Some(0);
+        }
         game
     }
 
-    fn get_current_row(&mut self) -> &mut BoardRow {
-        &mut self.rows[self.current_row]
+    /// Number of letters in each word.
+    pub fn length(&self) -> usize {
+        self.boards[0].answer.len()
     }
 
-    pub fn set_message(&mut self, message: &str) {
-        self.display_message = Some(message.into());
+    /// Number of guesses allowed.
+    pub fn rows(&self) -> usize {
+        self.boards[0].rows.len()
     }
 
-    pub fn clear_message(&mut self) {
-        self.display_message = None;
+    /// The current row of each board that hasn't been solved yet. Letters
+    /// typed go into all of them.
+    fn active_rows(&mut self) -> impl Iterator<Item = &mut BoardRow> {
+        let current_row = self.current_row;
+        self.boards
+            .iter_mut()
+            .filter(|board| !board.is_solved())
+            .map(move |board| &mut board.rows[current_row])
     }
 
+    /// Whether the game was won, or `None` while it's still being played.
     pub fn has_won(&self) -> Option<bool> {
         self.has_won
     }
 
-    pub fn answer(&self) -> &'static str {
-        self.answer
+    /// Number of boards being played at once.
+    pub fn board_count(&self) -> usize {
+        self.boards.len()
+    }
+
+    /// Every board, solved or not.
+    pub fn boards(&self) -> &[Board] {
+        &self.boards
+    }
+
+    /// Index of the row guesses are being typed into. It stays on the last
+    /// guess once the game is over.
+    pub fn current_row(&self) -> usize {
+        self.current_row
+    }
+
+    /// What is known about each letter from the boards still being played.
+    pub fn keyboard(&self) -> &Keyboard {
+        &self.keyboard
+    }
+
+    /// The answer of each board.
+    pub fn answers(&self) -> Vec<&str> {
+        self.boards
+            .iter()
+            .map(|board| board.answer.as_str())
+            .collect()
+    }
+
+    /// The answers of the boards that haven't been solved.
+    pub fn missed_answers(&self) -> Vec<&str> {
+        self.boards
+            .iter()
+            .filter(|board| !board.is_solved())
+            .map(|board| board.answer.as_str())
+            .collect()
+    }
+
+    /// The number of guesses submitted so far.
+    pub fn guesses_used(&self) -> usize {
+        match self.has_won {
+            Some(_) => self.current_row + 1,
+            None => self.current_row,
+        }
+    }
+
+    /// The daily puzzle number, if this is a daily game.
+    pub fn puzzle_number(&self) -> Option<u32> {
+        self.puzzle_number
+    }
+
+    /// Name of this game, e.g. "wordlers #512" for a daily puzzle.
+    pub fn title(&self) -> String {
+        match self.puzzle_number {
+            Some(n) => format!("wordlers #{n}"),
+            None if self.is_absurdle() => "wordlers absurdle".into(),
+            None => "wordlers".into(),
+        }
+    }
+
+    /// Describe how the game is set up, e.g. "Daily #512: 5 letters, 6
+    /// guesses, hard mode".
+    pub fn describe(&self) -> String {
+        let mode = match self.puzzle_number {
+            Some(n) => format!("Daily #{n}"),
+            None if self.is_absurdle() => "Absurdle".into(),
+            None => "Random word".into(),
+        };
+        let mut details = vec![
+            format!("{} letters", self.length()),
+            format!("{} guesses", self.rows()),
+        ];
+        if self.board_count() > 1 {
+            details.push(format!("{} boards", self.board_count()));
+        }
+        if self.hard_mode {
+            details.push("hard mode".into());
+        }
+        format!("{mode}: {}", details.join(", "))
+    }
+
+    /// Whether answers are chosen as guesses are made, as in Absurdle.
+    fn is_absurdle(&self) -> bool {
+        self.boards[0].candidates.is_some()
     }
 
     /// Event handler for letter keys.
     /// Returning true indicates that the app should repaint.
     pub fn try_accept_letter(&mut self, letter: char) -> bool {
-        self.clear_message();
-        letter.is_ascii_alphabetic()
-            && self
-                .get_current_row()
-                .try_accept_letter(letter.to_ascii_uppercase())
+        if !letter.is_ascii_alphabetic() {
+            return false;
+        }
+
+        let mut accepted = false;
+        for row in self.active_rows() {
+            accepted |= row.try_accept_letter(letter.to_ascii_uppercase());
+        }
+        accepted
     }
 
     /// Event handler for backspace.
     /// Returning true indicates that the app should repaint.
     pub fn try_delete_letter(&mut self) -> bool {
-        self.clear_message();
-        self.get_current_row().try_delete_letter()
+        let mut deleted = false;
+        for row in self.active_rows() {
+            deleted |= row.try_delete_letter();
+        }
+        deleted
+    }
+
+    /// Event handler for the delete key: clear the letter under the cursor,
+    /// leaving the cursor where it is.
+    /// Returning true indicates that the app should repaint.
+    pub fn try_clear_letter(&mut self) -> bool {
+        let mut cleared = false;
+        for row in self.active_rows() {
+            cleared |= row.try_clear_letter();
+        }
+        cleared
     }
 
-    /// Event handler for the enter key.
+    /// Event handler for the arrow, home and end keys.
     /// Returning true indicates that the app should repaint.
-    pub fn try_submit_guess(&mut self) -> bool {
-        self.clear_message();
+    pub fn try_move_cursor(&mut self, movement: CursorMove) -> bool {
+        let mut moved = false;
+        for row in self.active_rows() {
+            moved |= row.try_move_cursor(movement);
+        }
+        moved
+    }
+
+    /// Event handler for the enter key: submit the letters typed into the
+    /// current row. Returns the feedback the guess got on each board, as
+    /// `guess` does, or why it was rejected.
+    pub fn try_submit_guess(&mut self) -> Result<Vec<Option<Feedback>>, GuessError> {
+        if self.has_won.is_some() {
+            return Err(GuessError::GameOver);
+        }
+        let guess = self
+            .active_rows()
+            .next()
+            .and_then(|row| row.get_final_word())
+            .ok_or(GuessError::Incomplete)?;
+        self.submit(&guess)
+    }
+
+    /// Play a whole word as the next guess, replacing whatever was typed
+    /// into the current row. Returns the feedback (see `score`) the guess
+    /// got on each board, or `None` for boards solved before it. If the
+    /// guess is rejected, the reason is returned instead, and the word is
+    /// left typed in the current row if it fits.
+    pub fn guess(&mut self, word: &str) -> Result<Vec<Option<Feedback>>, GuessError> {
+        if self.has_won.is_some() {
+            return Err(GuessError::GameOver);
+        }
+        if word.len() != self.length() {
+            return Err(GuessError::WrongLength);
+        }
+        if !word.bytes().all(|b| b.is_ascii_alphabetic()) {
+            return Err(GuessError::NotInDictionary(word.into()));
+        }
 
-        if let Some(guess) = self.get_current_row().get_final_word() {
-            if !self.words.valid_guess(&guess) {
-                self.set_message(&format!("'{guess}' is not a valid word!"));
-                return true;
+        for row in self.active_rows() {
+            for (cell, letter) in row.cells.iter_mut().zip(word.chars()) {
+                *cell = Cell::Pending(Some(letter.to_ascii_uppercase()));
             }
+            row.current_cell = Some(row.cells.len());
+        }
+        self.submit(&word.to_ascii_lowercase())
+    }
 
-            let answer = self.answer;
-            self.get_current_row().check_guess(answer);
+    /// Check a complete guess, already typed into the current row, on every
+    /// unsolved board, and move on to the next row.
+    fn submit(&mut self, guess: &str) -> Result<Vec<Option<Feedback>>, GuessError> {
+        if !self.words.valid_guess(guess) {
+            return Err(GuessError::NotInDictionary(guess.into()));
+        }
+        if self.guesses().iter().any(|g| g == guess) {
+            return Err(GuessError::AlreadyGuessed(guess.into()));
+        }
+        if self.hard_mode {
+            if let Some(violation) = self.hard_mode_violation(guess) {
+                return Err(GuessError::HardModeViolation(violation));
+            }
+        }
 
-            if guess == self.answer {
-                self.has_won = Some(true);
-                return true;
+        // Check the guess on every unsolved board, freezing the ones it
+        // solves. In Absurdle mode, each board keeps answers that the boards
+        // before it didn't, so no two boards end up with the same answer.
+        let current_row = self.current_row;
+        let mut claimed = HashSet::new();
+        let patterns = self
+            .boards
+            .iter_mut()
+            .map(|board| {
+                if board.is_solved() {
+                    return None;
+                }
+                board.dodge(guess, &claimed);
+                claimed.extend(board.candidates.iter().flatten().cloned());
+                board.rows[current_row].check_guess(&board.answer);
+                if guess == board.answer {
+                    board.solved_row = Some(current_row);
+                }
+                board.rows[current_row].feedback()
+            })
+            .collect();
+        self.update_keyboard();
+
+        if self.boards.iter().all(Board::is_solved) {
+            self.has_won = Some(true);
+        } else if self.current_row < self.rows() - 1 {
+            self.current_row += 1;
+            for row in self.active_rows() {
+                row.current_cell = Some(0);
             }
+        } else {
+            // Out of guesses!
+            self.has_won = Some(false);
+        }
+        Ok(patterns)
+    }
+
+    /// Rebuild the keyboard from the boards still being played, so that
+    /// letters only look useful if they could help solve one. Once every
+    /// board is solved, all of them are shown.
+    fn update_keyboard(&mut self) {
+        let mut boards: Vec<_> = self.boards.iter().filter(|b| !b.is_solved()).collect();
+        if boards.is_empty() {
+            boards = self.boards.iter().collect();
+        }
+
+        self.keyboard = Keyboard::default();
+        for board in boards {
+            for row in board.rows.iter().filter(|row| row.feedback().is_some()) {
+                self.keyboard.update(row);
+            }
+        }
+    }
+
+    /// Get each guess submitted to a board along with the feedback it got
+    /// (see `score`). A solved board gets no guesses after the one that solved it.
+    pub fn feedback(&self, board: usize) -> Vec<(String, Feedback)> {
+        self.boards[board].feedback()
+    }
+
+    /// Every guess submitted so far.
+    pub fn guesses(&self) -> Vec<String> {
+        self.boards
+            .iter()
+            .map(Board::feedback)
+            .max_by_key(Vec::len)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(guess, _)| guess)
+            .collect()
+    }
+
+    /// Build the spoiler-free text for sharing a result: a header such as
+    /// "wordlers #512 4/6" followed by one row of colored squares per guess.
+    /// With several boards, the number of guesses each board took is given
+    /// instead, with the square for letters not in the word for a board
+    /// that wasn't solved. The squares are given for letters not in the
+    /// word, in the word, and correct.
+    pub fn share_text(&self, squares: [char; 3]) -> String {
+        let score = match self.has_won {
+            Some(true) => self.guesses_used().to_string(),
+            _ => "X".into(),
+        };
+        let hard = if self.hard_mode { "*" } else { "" };
+        let mut text = format!("{} {score}/{}{hard}\n", self.title(), self.rows());
+
+        if let [board] = &self.boards[..] {
+            for row in &board.rows[..self.guesses_used()] {
+                text.push('\n');
+                text.extend(row.cells.iter().map(|cell| match cell {
+                    Cell::Pending(_) | Cell::NotInWord(_) => squares[0],
+                    Cell::InWord(_) => squares[1],
+                    Cell::Correct(_) => squares[2],
+                }));
+            }
+        } else {
+            let results: Vec<_> = self
+                .boards
+                .iter()
+                .map(|board| match board.solved_row {
+                    Some(row) => (row + 1).to_string(),
+                    None => squares[0].into(),
+                })
+                .collect();
+            text.push('\n');
+            text.push_str(&results.join(" "));
+        }
+
+        text
+    }
+
+    /// Whether the game has been started (a letter typed or a guess made)
+    /// but not finished.
+    pub fn in_progress(&self) -> bool {
+        self.has_won.is_none()
+            && (self.current_row > 0
+                || self.boards[0].rows[0]
+                    .cells
+                    .iter()
+                    .any(|cell| *cell != Cell::Pending(None)))
+    }
+
+    /// Serialize an unfinished game so it can be resumed later. The save is
+    /// made of lines such as:
+    ///
+    /// ```text
+    /// wordlers save 3
+    /// words 5 6177fb7f460070e1
+    /// mode daily 512
+    /// hard false
+    /// rows 6
+    /// answer 967
+    /// guess sound
+    /// pending ea
+    /// checksum 9c03779865fea498
+    /// ```
+    ///
+    /// The words are the word length and a hash of the answer list, since the
+    /// game can only be resumed with the same list. The mode is `random`,
+    /// `absurdle`, or `daily` with a puzzle number, and there is one answer
+    /// per board, given by its position in the answer list so that it isn't
+    /// spoiled by a look at the file. There is one
+    /// `guess` line per submitted guess, and the letters typed so far are
+    /// pending, with `.` for any gaps between them. The checksum covers every
+    /// line before it so that damaged saves can be rejected. It doesn't stop
+    /// a save from being edited on purpose.
+    pub fn save(&self) -> String {
+        let mode = match self.puzzle_number {
+            Some(n) => format!("daily {n}"),
+            None if self.is_absurdle() => "absurdle".into(),
+            None => "random".into(),
+        };
+        let answer_indices: Vec<_> = self
+            .answers()
+            .iter()
+            .map(|&answer| {
+                let answers = self.words.answers();
+                let index = answers.iter().position(|a| a == answer);
+                index
+                    .expect("answers come from the answer list")
+                    .to_string()
+            })
+            .collect();
+        let mut lines = vec![
+            Self::SAVE_HEADER.to_string(),
+            format!(
+                "words {} {:016x}",
+                self.length(),
+                answers_hash(self.words.answers())
+            ),
+            format!("mode {mode}"),
+            format!("hard {}", self.hard_mode),
+            format!("rows {}", self.rows()),
+            format!("answer {}", answer_indices.join(" ")),
+        ];
+        for guess in self.guesses() {
+            lines.push(format!("guess {guess}"));
+        }
+        let pending: String = self
+            .boards
+            .iter()
+            .find(|board| !board.is_solved())
+            .map(|board| &board.rows[self.current_row].cells[..])
+            .unwrap_or_default()
+            .iter()
+            .map(|cell| match cell {
+                Cell::Pending(Some(c)) => c.to_ascii_lowercase(),
+                _ => '.',
+            })
+            .collect();
+        lines.push(format!("pending {}", pending.trim_end_matches('.')));
 
-            if self.current_row < 5 {
-                self.current_row += 1;
-                self.get_current_row().current_cell = Some(0);
+        let body = lines.join("\n");
+        format!("{body}\nchecksum {:016x}\n", checksum(&body))
+    }
+
+    /// Restore a game serialized by `save`. A save made with other words is
+    /// rejected as such. Each guess is replayed, so a save that doesn't
+    /// describe a valid unfinished game is rejected as invalid, as is one
+    /// whose checksum doesn't match.
+    pub fn restore(save: &str, words: Rc<Words>) -> Result<Self, RestoreError> {
+        let (body, saved_checksum) = save
+            .trim_end()
+            .rsplit_once('\n')
+            .and_then(|(body, last)| Some((body, last.strip_prefix("checksum ")?)))
+            .ok_or_else(|| "missing checksum".to_string())?;
+        if saved_checksum != format!("{:016x}", checksum(body)) {
+            return Err(RestoreError::Invalid("checksum doesn't match".into()));
+        }
+
+        let mut lines = body.lines();
+        if lines.next() != Some(Self::SAVE_HEADER) {
+            return Err(RestoreError::Invalid("unknown save format".into()));
+        }
+
+        let saved_words = save_field(&mut lines, "words")?;
+        let (length, hash) = saved_words
+            .split_once(' ')
+            .and_then(|(length, hash)| Some((length.parse().ok()?, hash)))
+            .ok_or_else(|| format!("bad words '{saved_words}'"))?;
+        if length != words.length() {
+            return Err(RestoreError::OtherLength(length));
+        }
+        if hash != format!("{:016x}", answers_hash(words.answers())) {
+            return Err(RestoreError::OtherAnswers);
+        }
+
+        let mode = save_field(&mut lines, "mode")?;
+        let (mode, puzzle_number) = match mode.split_once(' ') {
+            Some(("daily", n)) => (
+                Mode::Daily,
+                Some(n.parse().map_err(|_| format!("bad puzzle number '{n}'"))?),
+            ),
+            None if mode == "random" => (Mode::Random, None),
+            None if mode == "absurdle" => (Mode::Absurdle, None),
+            _ => return Err(RestoreError::Invalid(format!("bad mode '{mode}'"))),
+        };
+        let hard_mode = match save_field(&mut lines, "hard")? {
+            "true" => true,
+            "false" => false,
+            other => return Err(RestoreError::Invalid(format!("bad hard mode '{other}'"))),
+        };
+        let rows = save_field(&mut lines, "rows")?;
+        let rows = rows
+            .parse()
+            .ok()
+            .filter(|n| (1..=Settings::MAX_ROWS).contains(n))
+            .ok_or_else(|| format!("bad row count '{rows}'"))?;
+        let answers = save_field(&mut lines, "answer")?
+            .split(' ')
+            .map(|index| {
+                index
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| words.answers().get(i))
+                    .cloned()
+                    .ok_or_else(|| format!("bad answer '{index}'"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if answers.len() > Settings::MAX_BOARDS {
+            return Err(RestoreError::Invalid(format!(
+                "more than {} answers",
+                Settings::MAX_BOARDS
+            )));
+        }
+
+        // Daily games are rebuilt as random games with the saved answers.
+        let settings = Settings {
+            mode: if mode == Mode::Absurdle {
+                Mode::Absurdle
             } else {
-                // Out of guesses!
-                self.has_won = Some(false);
+                Mode::Random
+            },
+            hard_mode,
+            rows,
+            boards: answers.len(),
+        };
+        let mut game = Self::new(&settings, words);
+        for (board, answer) in game.boards.iter_mut().zip(answers) {
+            board.answer = answer;
+        }
+        game.puzzle_number = puzzle_number;
+
+        // Replay the guesses, which must all be accepted without ending the
+        // game, followed by the letters typed in the current row.
+        let mut lines = lines.peekable();
+        while let Some(guess) = lines.peek().and_then(|line| line.strip_prefix("guess ")) {
+            lines.next();
+            let row = game.current_row;
+            if game.guess(guess).is_err() || game.current_row == row {
+                return Err(RestoreError::Invalid(format!("bad guess '{guess}'")));
             }
-            true
+        }
+
+        let pending = save_field(&mut lines, "pending")?;
+        if !pending.chars().all(|c| match c {
+            '.' => game.try_move_cursor(CursorMove::Right),
+            c => c.is_ascii_lowercase() && game.try_accept_letter(c),
+        }) {
+            return Err(RestoreError::Invalid(format!(
+                "bad pending letters '{pending}'"
+            )));
+        }
+        if let Some(line) = lines.next() {
+            return Err(RestoreError::Invalid(format!("unexpected line '{line}'")));
+        }
+
+        Ok(game)
+    }
+
+    /// The answers still possible and the guesses expected to narrow them
+    /// down the most. With several boards, the hint is for the unsolved board
+    /// closest to being solved. Returns `None` once every board is solved.
+    pub fn hint(&self) -> Option<Hint<'_>> {
+        let answers = || self.words.answers().iter().map(String::as_str);
+        let (board, possible) = self
+            .boards
+            .iter()
+            .enumerate()
+            .filter(|(_, board)| !board.is_solved())
+            .map(|(i, board)| (i, solver::possible_answers(answers(), &board.feedback())))
+            .min_by_key(|(_, possible)| possible.len())?;
+
+        // Suggestions are only worth working out while there's a choice.
+        // Before the first guess they depend only on the word lists, so
+        // every guess is scored once and kept, rather than only the answers.
+        let suggestions = if possible.len() <= 1 {
+            Vec::new()
+        } else if self.current_row == 0 {
+            let opening = self.words.opening_guesses().iter();
+            opening
+                .map(|(word, bits)| Suggestion { word, bits: *bits })
+                .collect()
         } else {
-            false
+            // Only suggest guesses that would be accepted.
+            let mut candidates: Vec<_> = self
+                .words
+                .guesses()
+                .filter(|guess| !self.hard_mode || self.hard_mode_violation(guess).is_none())
+                .collect();
+            candidates.sort_unstable();
+            solver::rank_guesses(candidates, &possible, Words::OPENING_GUESSES)
+        };
+        Some(Hint {
+            board,
+            possible,
+            suggestions,
+        })
+    }
+
+    /// Check a guess against the hints revealed by previous rows. Returns the
+    /// first broken rule, if any. With several boards, a guess only has to
+    /// follow the hints of one unsolved board.
+    fn hard_mode_violation(&self, guess: &str) -> Option<HardModeViolation> {
+        let violations: Vec<_> = self
+            .boards
+            .iter()
+            .filter(|board| !board.is_solved())
+            .map(|board| board.hard_mode_violation(self.current_row, guess))
+            .collect();
+
+        if violations.iter().any(Option::is_none) {
+            None
+        } else {
+            violations.into_iter().flatten().next()
+        }
+    }
+}
+
+/// One of the boards in a game, with its own answer and history of guesses.
+pub struct Board {
+    answer: String,
+    rows: Vec<BoardRow>,
+    /// The row where the answer was guessed. The board is frozen after it.
+    solved_row: Option<usize>,
+    /// In Absurdle mode, the answers still possible. The answer is then just
+    /// one of these, and can change with every guess.
+    candidates: Option<Vec<String>>,
+}
+
+impl Board {
+    fn new(answer: &str, rows: usize, candidates: Option<Vec<String>>) -> Self {
+        Self {
+            answer: answer.into(),
+            rows: vec![BoardRow::empty(answer.len()); rows],
+            solved_row: None,
+            candidates,
         }
     }
 
-    pub fn paint(
-        &self,
-        screen: &mut impl Write,
-        top_left: (u16, u16),
-        colors: &ColorScheme,
-    ) -> io::Result<()> {
-        let (x, y) = top_left;
-
-        for (i, row) in self.rows.iter().enumerate() {
-            let y_offset = (i as u16) * Cell::SIZE.1;
-            row.paint(screen, (x, y + y_offset), colors, i == self.current_row)?;
-        }
-
-        if let Some(message) = &self.display_message {
-            // Write up to two wrapped message lines beneath the board.
-            let lines = textwrap::wrap(message, Self::BOARD_SIZE.0 as usize);
-            for i in 0..2 {
-                if let Some(line) = lines.get(i) {
-                    let y_offset = Self::BOARD_SIZE.1 - 2 + (i as u16);
-                    write!(
-                        screen,
-                        "{}{}{}{}",
-                        termion::cursor::Goto(x, y + y_offset),
-                        termion::color::Bg(colors.game_bg),
-                        termion::color::Fg(colors.text_base),
-                        line,
-                    )?;
+    /// In Absurdle mode, give `guess` whichever pattern leaves the most
+    /// candidates, and keep only those. Candidates `claimed` by other boards
+    /// are left out, unless there are no others. Has no effect otherwise.
+    fn dodge(&mut self, guess: &str, claimed: &HashSet<String>) {
+        let Some(candidates) = &mut self.candidates else {
+            return;
+        };
+
+        let mut possible: Vec<_> = candidates
+            .iter()
+            .filter(|answer| !claimed.contains(*answer))
+            .map(String::as_str)
+            .collect();
+        if possible.is_empty() {
+            possible = candidates.iter().map(String::as_str).collect();
+        }
+        if let Some(pattern) = solver::largest_group(guess, &possible) {
+            let possible: HashSet<_> = possible.into_iter().map(String::from).collect();
+            candidates
+                .retain(|answer| possible.contains(answer) && score(guess, answer) == pattern);
+            self.answer = candidates[0].clone();
+        }
+    }
+
+    /// Whether the answer has been guessed.
+    pub fn is_solved(&self) -> bool {
+        self.solved_row.is_some()
+    }
+
+    /// Every row of the board, including those not guessed yet.
+    pub fn rows(&self) -> &[BoardRow] {
+        &self.rows
+    }
+
+    /// Each finalized row's guess and the feedback it got.
+    fn feedback(&self) -> Vec<(String, Feedback)> {
+        self.rows
+            .iter()
+            .filter_map(|row| Some((row.get_final_word()?, row.feedback()?)))
+            .collect()
+    }
+
+    /// Check a guess against the hints revealed by the rows before
+    /// `current_row`. Returns the first broken rule, if any.
+    ///
+    /// Green letters must stay in place, and yellow letters must be reused
+    /// (as many times as a single row has revealed them).
+    fn hard_mode_violation(&self, current_row: usize, guess: &str) -> Option<HardModeViolation> {
+        let guess: Vec<_> = guess.to_ascii_uppercase().chars().collect();
+        let finalized = &self.rows[..current_row];
+
+        for row in finalized {
+            for (i, cell) in row.cells.iter().enumerate() {
+                if let Cell::Correct(l) = *cell {
+                    if guess[i] != l {
+                        return Some(HardModeViolation::Correct {
+                            position: i,
+                            letter: l,
+                        });
+                    }
+                }
+            }
+        }
+
+        for row in finalized {
+            let mut required: HashMap<char, usize> = HashMap::new();
+            for &cell in &row.cells {
+                if let Cell::Correct(l) | Cell::InWord(l) = cell {
+                    *required.entry(l).or_default() += 1;
+                }
+            }
+
+            // Report letters in the order they appear in the row.
+            for &cell in &row.cells {
+                if let Cell::InWord(l) = cell {
+                    let count = required[&l];
+                    if guess.iter().filter(|&&c| c == l).count() < count {
+                        return Some(HardModeViolation::InWord { letter: l, count });
+                    }
                 }
             }
         }
 
-        Ok(())
+        None
+    }
+}
+
+/// Get the value of the next line of a saved game, which must start with
+/// the field's name.
+fn save_field<'a>(
+    lines: &mut impl Iterator<Item = &'a str>,
+    name: &str,
+) -> Result<&'a str, String> {
+    let line = lines.next().ok_or_else(|| format!("missing {name}"))?;
+    line.strip_prefix(name)
+        .and_then(|value| value.strip_prefix(' '))
+        .ok_or_else(|| format!("expected {name}, found '{line}'"))
+}
+
+/// A hash of an answer list, for noticing when a game is resumed with
+/// another list.
+fn answers_hash(answers: &[String]) -> u64 {
+    checksum(&answers.join("\n"))
+}
+
+/// A 64-bit FNV-1a hash of some text, for noticing when a saved game has
+/// been damaged. Anyone can compute it, so it doesn't show that a save
+/// wasn't edited.
+fn checksum(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Why a saved game couldn't be restored.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RestoreError {
+    /// The game was saved with words of another length, given here.
+    OtherLength(usize),
+    /// The game was saved with another answer list of the same length.
+    OtherAnswers,
+    /// The save is damaged, or doesn't describe a valid unfinished game.
+    Invalid(String),
+}
+
+impl From<String> for RestoreError {
+    fn from(message: String) -> Self {
+        Self::Invalid(message)
+    }
+}
+
+impl fmt::Display for RestoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::OtherLength(length) => write!(f, "saved with {length}-letter words"),
+            Self::OtherAnswers => write!(f, "saved with another answer list"),
+            Self::Invalid(message) => f.write_str(message),
+        }
     }
 }
 
+/// Why a guess was rejected. Nothing changes when a guess is rejected,
+/// except that `Game::guess` leaves the word typed in the current row.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GuessError {
+    /// The current row isn't filled in yet.
+    Incomplete,
+    /// The word has the wrong number of letters.
+    WrongLength,
+    /// The word isn't in the list of valid guesses.
+    NotInDictionary(String),
+    /// In hard mode, the word ignores a hint revealed earlier.
+    HardModeViolation(HardModeViolation),
+    /// The game has already been won or lost.
+    GameOver,
+    /// The word has already been guessed.
+    AlreadyGuessed(String),
+}
+
+/// A hint that a guess in hard mode must use but doesn't.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HardModeViolation {
+    /// A letter found to be correct must stay at `position` (counting from
+    /// 0).
+    Correct { position: usize, letter: char },
+    /// A letter found to be in the word must be used at least `count` times,
+    /// as many as a single earlier row has revealed.
+    InWord { letter: char, count: usize },
+}
+
+/// Help choosing the next guess, for one board.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hint<'a> {
+    /// Index of the board the hint is for.
+    pub board: usize,
+    /// Every answer still possible on that board.
+    pub possible: Vec<&'a str>,
+    /// The guesses expected to narrow down the possible answers the most,
+    /// best first. Empty unless there's more than one possible answer.
+    pub suggestions: Vec<Suggestion<'a>>,
+}
+
+/// A way of moving the cursor within the current row.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CursorMove {
+    Left,
+    Right,
+    Home,
+    End,
+}
+
 /// Single row of the game board.
-#[derive(Copy, Clone)]
-struct BoardRow {
-    cells: [Cell; 5],
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BoardRow {
+    cells: Vec<Cell>,
     current_cell: Option<usize>,
 }
 
 impl BoardRow {
-    fn empty() -> Self {
+    fn empty(length: usize) -> Self {
         Self {
-            cells: [Cell::Pending(None); 5],
+            cells: vec![Cell::Pending(None); length],
             current_cell: None,
         }
     }
 
+    /// The row's cells, one per letter.
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    /// Where the next letter typed will go: a cell, or just past the end of
+    /// the row. Only the current row has a cursor.
+    pub fn cursor(&self) -> Option<usize> {
+        self.current_cell
+    }
+
     /// If all cells are filled, return the string they make.
     /// Otherwise, return None.
-    fn get_final_word(&self) -> Option<String> {
+    pub fn get_final_word(&self) -> Option<String> {
         let mut word = String::new();
-        for cell in self.cells {
+        for &cell in &self.cells {
             let letter = match cell {
                 Cell::Pending(None) => return None,
                 Cell::Pending(Some(l))
@@ -173,14 +900,14 @@ impl BoardRow {
     }
 
     fn get_current_cell(&mut self) -> Option<&mut Cell> {
-        self.current_cell.map(|i| &mut self.cells[i])
+        self.current_cell.and_then(|i| self.cells.get_mut(i))
     }
 
     /// Row handler for letter keys.
     /// Returning true indicates that the app should repaint.
     fn try_accept_letter(&mut self, letter: char) -> bool {
         if let Some(i) = self.current_cell {
-            if i < 5 {
+            if i < self.cells.len() {
                 *self.get_current_cell().unwrap() = Cell::Pending(Some(letter));
                 self.current_cell = Some(i + 1);
                 true
@@ -192,6 +919,42 @@ impl BoardRow {
         }
     }
 
+    /// Row handler for the delete key.
+    /// Returning true indicates that the app should repaint.
+    fn try_clear_letter(&mut self) -> bool {
+        match self.get_current_cell() {
+            Some(cell) if *cell != Cell::Pending(None) => {
+                *cell = Cell::Pending(None);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Row handler for cursor keys. The cursor can be anywhere in the row, or
+    /// just past its end.
+    /// Returning true indicates that the app should repaint.
+    fn try_move_cursor(&mut self, movement: CursorMove) -> bool {
+        let Some(i) = self.current_cell else {
+            return false;
+        };
+
+        let end = self.cells.len();
+        let target = match movement {
+            CursorMove::Left => i.saturating_sub(1),
+            CursorMove::Right => (i + 1).min(end),
+            CursorMove::Home => 0,
+            // Just past the last letter typed.
+            CursorMove::End => self
+                .cells
+                .iter()
+                .rposition(|&cell| cell != Cell::Pending(None))
+                .map_or(0, |last| last + 1),
+        };
+        self.current_cell = Some(target);
+        target != i
+    }
+
     /// Row handler for backspace.
     /// Returning true indicates that the app should repaint.
     fn try_delete_letter(&mut self) -> bool {
@@ -210,8 +973,6 @@ impl BoardRow {
 
     /// Finalize the cells of this row according to the answer.
     ///
-    /// This is the "meat" of the wordle logic.
-    ///
     /// # Panics
     /// Panics if called on a row that is not complete.
     fn check_guess(&mut self, answer: &str) {
@@ -219,82 +980,34 @@ impl BoardRow {
             .get_final_word()
             .expect("Should only be called when all letters are here");
 
-        // Closure to turn a string into a map of letters
-        // to the indices at which they occur.
-        let get_char_map = |s: &str| {
-            s.char_indices().fold(HashMap::new(), |mut map, (i, c)| {
-                let entry = map.entry(c).or_insert(Vec::new());
-                entry.push(i);
-                map
-            })
-        };
-
-        let guess_chars = get_char_map(&guess);
-        let answer_chars = get_char_map(answer);
-
-        // Consider each letter individually.
-        for (letter, guess_indices) in guess_chars.into_iter() {
-            // If the letter is in the answer...
-            if let Some(answer_indices) = answer_chars.get(&letter) {
-                let mut num_reported = 0;
-                let mut potential_yellows = Vec::with_capacity(guess_indices.len());
-
-                // Start with exact matches.
-                for i in guess_indices {
-                    if answer_indices.contains(&i) {
-                        self.cells[i].correct();
-                        num_reported += 1;
-                    } else {
-                        potential_yellows.push(i);
-                    }
-                }
-
-                // Fill in yellows from left to right as long as there are
-                // "un-greened" instances of this letter in the answer.
-                for i in potential_yellows {
-                    if num_reported < answer_indices.len() {
-                        self.cells[i].in_word();
-                        num_reported += 1;
-                    } else {
-                        self.cells[i].not_in_word();
-                    }
-                }
-            } else {
-                // This letter is not in the answer.
-                for i in guess_indices {
-                    self.cells[i].not_in_word();
-                }
+        for (cell, mark) in self.cells.iter_mut().zip(score(&guess, answer).marks()) {
+            match mark {
+                Mark::Correct => cell.correct(),
+                Mark::Present => cell.in_word(),
+                Mark::Absent => cell.not_in_word(),
             }
         }
     }
 
-    fn paint(
-        &self,
-        screen: &mut impl Write,
-        top_left: (u16, u16),
-        colors: &ColorScheme,
-        active: bool,
-    ) -> io::Result<()> {
-        let (x, y) = top_left;
-
-        for (i, cell) in self.cells.iter().enumerate() {
-            let x_offset = (i as u16) * Cell::SIZE.0;
-            cell.paint(
-                screen,
-                (x + x_offset, y),
-                colors,
-                active,
-                self.current_cell == Some(i),
-            )?;
-        }
-
-        Ok(())
+    /// Get the feedback a finalized row got (see `score`), or `None` if the
+    /// row hasn't been checked.
+    pub fn feedback(&self) -> Option<Feedback> {
+        self.cells
+            .iter()
+            .map(|cell| match cell {
+                Cell::Pending(_) => None,
+                Cell::NotInWord(_) => Some(Mark::Absent),
+                Cell::InWord(_) => Some(Mark::Present),
+                Cell::Correct(_) => Some(Mark::Correct),
+            })
+            .collect()
     }
 }
 
 /// A single letter cell.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-enum Cell {
+pub enum Cell {
+    /// A letter typed but not yet submitted, or an empty cell.
     Pending(Option<char>),
     NotInWord(char),
     InWord(char),
@@ -302,9 +1015,6 @@ enum Cell {
 }
 
 impl Cell {
-    /// Size (w, h) of a single cell, for reference.
-    const SIZE: (u16, u16) = (5, 3);
-
     /// Update `Cell::Pending` variant to `Cell::NotInWord`.
     /// Has no effect if called on a finalized or empty cell.
     fn not_in_word(&mut self) {
@@ -329,52 +1039,18 @@ impl Cell {
         }
     }
 
-    fn paint(
-        &self,
-        screen: &mut impl Write,
-        top_left: (u16, u16),
-        colors: &ColorScheme,
-        row_active: bool,
-        cell_active: bool,
-    ) -> io::Result<()> {
-        let (x, y) = top_left;
-        let bg_color = colors.game_bg;
-        let cell_char = self.get_char();
-
-        let base_color = if row_active {
-            if cell_active {
-                colors.cell_active
-            } else {
-                colors.cell_row_active
-            }
-        } else {
-            colors.cell_base
-        };
-
-        let (text_color, cell_color) = match *self {
-            Self::Pending(_) | Self::NotInWord(_) => (colors.text_base, base_color),
-            Self::InWord(_) => (colors.text_inverted, colors.cell_in_word),
-            Self::Correct(_) => (colors.text_inverted, colors.cell_correct),
-        };
-
-        write!(
-            screen,
-            "{}{}{} ▄▄▄ {} █{}{}{}{}{}█ {} ▀▀▀ ",
-            termion::cursor::Goto(x, y), // Row 1.
-            termion::color::Bg(bg_color),
-            termion::color::Fg(cell_color),
-            termion::cursor::Goto(x, y + 1), // Row 2.
-            termion::color::Bg(cell_color),
-            termion::color::Fg(text_color),
-            cell_char,
-            termion::color::Bg(bg_color),
-            termion::color::Fg(cell_color),
-            termion::cursor::Goto(x, y + 2), // Row 3.
-        )
+    /// How much this cell reveals about its letter, for comparing hints.
+    fn rank(&self) -> u8 {
+        match *self {
+            Self::Pending(_) => 0,
+            Self::NotInWord(_) => 1,
+            Self::InWord(_) => 2,
+            Self::Correct(_) => 3,
+        }
     }
 
     /// Get the character to display.
-    fn get_char(&self) -> char {
+    pub fn get_char(&self) -> char {
         match *self {
             Self::Pending(None) => ' ',
             Self::Pending(Some(c)) => c,
@@ -383,18 +1059,45 @@ impl Cell {
     }
 }
 
+/// What is known about each letter from the guesses made so far.
+#[derive(Clone, Debug, Default)]
+pub struct Keyboard {
+    letters: HashMap<char, Cell>,
+}
+
+impl Keyboard {
+    /// Record the finalized cells of a row, keeping the best state seen for
+    /// each letter: correct beats in-word, which beats not-in-word.
+    fn update(&mut self, row: &BoardRow) {
+        for &cell in &row.cells {
+            let letter = cell.get_char();
+            let known = self.letters.entry(letter).or_insert(cell);
+            if cell.rank() > known.rank() {
+                *known = cell;
+            }
+        }
+    }
+
+    /// Get the best-known state of a letter, if it has been guessed.
+    /// Letters are uppercase.
+    pub fn get(&self, letter: char) -> Option<Cell> {
+        self.letters.get(&letter).copied()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const SQUARES: [char; 3] = ['⬛', '🟨', '🟩'];
+
     fn get_pending_row_for_str(s: &str) -> BoardRow {
-        assert_eq!(s.len(), 5);
-        let cells: Vec<_> = s
+        let cells = s
             .chars()
             .map(|c| Cell::Pending(Some(c.to_ascii_uppercase())))
             .collect();
         BoardRow {
-            cells: [cells[0], cells[1], cells[2], cells[3], cells[4]],
+            cells,
             current_cell: None,
         }
     }
@@ -443,6 +1146,423 @@ mod tests {
         );
     }
 
+    fn get_game_with_guesses(answer: &str, guesses: &[&str]) -> Game {
+        let mut game = Game::new(&Settings::default(), Rc::new(Words::new(5)));
+        game.boards[0].answer = answer.into();
+        for guess in guesses {
+            submit(&mut game, guess);
+        }
+        game
+    }
+
+    fn submit(game: &mut Game, guess: &str) {
+        for c in guess.chars() {
+            game.try_accept_letter(c);
+        }
+        let _ = game.try_submit_guess();
+    }
+
+    #[test]
+    fn enforces_hard_mode() {
+        let game = get_game_with_guesses("heart", &["hoist"]);
+        assert_eq!(
+            game.hard_mode_violation("smart"),
+            Some(HardModeViolation::Correct {
+                position: 0,
+                letter: 'H'
+            })
+        );
+        assert_eq!(game.hard_mode_violation("heist"), None);
+
+        let game = get_game_with_guesses("heart", &["earth"]);
+        assert_eq!(
+            game.hard_mode_violation("tread"),
+            Some(HardModeViolation::InWord {
+                letter: 'H',
+                count: 1
+            })
+        );
+        assert_eq!(game.hard_mode_violation("heart"), None);
+
+        let game = get_game_with_guesses("cacti", &["civic"]);
+        assert_eq!(
+            game.hard_mode_violation("tacit"),
+            Some(HardModeViolation::Correct {
+                position: 0,
+                letter: 'C'
+            })
+        );
+        assert_eq!(
+            game.hard_mode_violation("cabin"),
+            Some(HardModeViolation::InWord {
+                letter: 'C',
+                count: 2
+            })
+        );
+        assert_eq!(game.hard_mode_violation("cacti"), None);
+    }
+
+    #[test]
+    fn rejects_hard_mode_violations() {
+        let mut game = get_game_with_guesses("heart", &["hoist"]);
+        game.hard_mode = true;
+        let violation = HardModeViolation::Correct {
+            position: 0,
+            letter: 'H',
+        };
+        assert_eq!(
+            game.guess("smart"),
+            Err(GuessError::HardModeViolation(violation))
+        );
+        assert_eq!(game.current_row, 1);
+
+        game.hard_mode = false;
+        assert!(game.try_submit_guess().is_ok());
+        assert_eq!(game.current_row, 2);
+    }
+
+    #[test]
+    fn tracks_best_known_letters() {
+        let game = get_game_with_guesses("heart", &["tread", "earth"]);
+        assert_eq!(game.keyboard.get('H'), Some(Cell::InWord('H')));
+        assert_eq!(game.keyboard.get('E'), Some(Cell::InWord('E')));
+        assert_eq!(game.keyboard.get('T'), Some(Cell::InWord('T')));
+        assert_eq!(game.keyboard.get('D'), Some(Cell::NotInWord('D')));
+        assert_eq!(game.keyboard.get('Q'), None);
+
+        // A later green upgrades the key, and a later yellow doesn't downgrade it.
+        let game = get_game_with_guesses("heart", &["earth", "heist", "earth"]);
+        assert_eq!(game.keyboard.get('H'), Some(Cell::Correct('H')));
+        assert_eq!(game.keyboard.get('T'), Some(Cell::Correct('T')));
+        assert_eq!(game.keyboard.get('A'), Some(Cell::InWord('A')));
+    }
+
+    #[test]
+    fn builds_share_text() {
+        let game = get_game_with_guesses("heart", &["sound", "earth", "heart"]);
+        assert_eq!(
+            game.share_text(SQUARES),
+            "wordlers 3/6\n\n⬛⬛⬛⬛⬛\n🟨🟨🟨🟨🟨\n🟩🟩🟩🟩🟩"
+        );
+        assert_eq!(
+            game.share_text(['⬜', '🟦', '🟧']),
+            "wordlers 3/6\n\n⬜⬜⬜⬜⬜\n🟦🟦🟦🟦🟦\n🟧🟧🟧🟧🟧"
+        );
+
+        let guesses = ["heist", "sound", "crane", "plumb", "fjord", "tipsy"];
+        let mut game = get_game_with_guesses("heart", &guesses);
+        game.hard_mode = true;
+        game.puzzle_number = Some(512);
+        assert!(game
+            .share_text(SQUARES)
+            .starts_with("wordlers #512 X/6*\n\n🟩🟩⬛⬛🟩\n"));
+    }
+
+    #[test]
+    fn saves_and_restores_games() {
+        let words = Rc::new(Words::new(5));
+        let mut game = get_game_with_guesses("heart", &["sound", "earth"]);
+        game.puzzle_number = Some(512);
+        assert!(game.in_progress());
+        game.try_accept_letter('h');
+        game.try_accept_letter('e');
+
+        let save = game.save();
+        let restored = Game::restore(&save, Rc::clone(&words)).unwrap();
+        assert_eq!(restored.answers(), ["heart"]);
+        assert_eq!(restored.puzzle_number(), Some(512));
+        assert_eq!(restored.feedback(0), game.feedback(0));
+        assert_eq!(restored.keyboard.get('E'), game.keyboard.get('E'));
+        assert_eq!(restored.save(), save);
+
+        // The answer isn't written out, and damaged saves are rejected.
+        assert!(!save.contains("heart"));
+        let damaged = save.replace("answer 967", "answer 968");
+        assert_eq!(
+            Game::restore(&damaged, Rc::clone(&words)).err(),
+            Some(RestoreError::Invalid("checksum doesn't match".into()))
+        );
+        let truncated = &save[..save.find("checksum").unwrap()];
+        assert!(Game::restore(truncated, Rc::clone(&words)).is_err());
+
+        // Saves made with other words are told apart, so they can be kept.
+        assert_eq!(
+            Game::restore(&save, Rc::new(Words::new(6))).err(),
+            Some(RestoreError::OtherLength(5))
+        );
+        let edit = |from: &str, to: &str| {
+            let body = save
+                .lines()
+                .filter(|line| !line.starts_with("checksum"))
+                .collect::<Vec<_>>()
+                .join("\n")
+                .replace(from, to);
+            format!("{body}\nchecksum {:016x}\n", checksum(&body))
+        };
+        let hash = format!("{:016x}", answers_hash(words.answers()));
+        assert_eq!(
+            Game::restore(&edit(&hash, "0123456789abcdef"), Rc::clone(&words)).err(),
+            Some(RestoreError::OtherAnswers)
+        );
+
+        // Saves of finished games are rejected, even with a valid checksum.
+        assert_eq!(
+            Game::restore(&edit("guess earth", "guess earth\nguess heart"), words).err(),
+            Some(RestoreError::Invalid("bad guess 'heart'".into()))
+        );
+
+        let fresh = get_game_with_guesses("heart", &[]);
+        assert!(!fresh.in_progress());
+    }
+
+    #[test]
+    fn plays_other_lengths() {
+        let settings = Settings {
+            rows: Settings::default_rows(9, 1),
+            ..Default::default()
+        };
+        let mut game = Game::new(&settings, Rc::new(Words::new(9)));
+        game.boards[0].answer = "wonderful".into();
+        assert_eq!(game.rows(), 10);
+        assert_eq!(game.length(), 9);
+
+        submit(&mut game, "beautiful");
+        assert_eq!(game.current_row, 1);
+        submit(&mut game, "wonderful");
+        assert_eq!(game.has_won(), Some(true));
+    }
+
+    fn get_game_with_boards(answers: &[&str]) -> Game {
+        let settings = Settings {
+            rows: Settings::default_rows(5, answers.len()),
+            boards: answers.len(),
+            ..Default::default()
+        };
+        let mut game = Game::new(&settings, Rc::new(Words::new(5)));
+        for (board, answer) in game.boards.iter_mut().zip(answers) {
+            board.answer = answer.to_string();
+        }
+        game
+    }
+
+    #[test]
+    fn plays_several_boards() {
+        let mut game = get_game_with_boards(&["heart", "sound"]);
+        assert_eq!(game.rows(), 7);
+
+        // A solved board is frozen while the others keep going.
+        submit(&mut game, "heart");
+        assert_eq!(game.has_won(), None);
+        assert_eq!(game.missed_answers(), ["sound"]);
+        game.try_accept_letter('s');
+        assert_eq!(game.boards[0].rows[1].cells[0], Cell::Pending(None));
+        assert_eq!(game.boards[1].rows[1].cells[0], Cell::Pending(Some('S')));
+
+        game.try_delete_letter();
+        submit(&mut game, "sound");
+        assert_eq!(game.has_won(), Some(true));
+        assert_eq!(game.guesses(), ["heart", "sound"]);
+        assert_eq!(game.feedback(0).len(), 1);
+        assert_eq!(game.feedback(1).len(), 2);
+        assert_eq!(game.share_text(SQUARES), "wordlers 2/7\n\n1 2");
+
+        let mut game = get_game_with_boards(&["heart", "sound"]);
+        for guess in [
+            "sound", "earth", "hoist", "crane", "tread", "plumb", "fjord",
+        ] {
+            submit(&mut game, guess);
+        }
+        assert_eq!(game.has_won(), Some(false));
+        assert_eq!(game.missed_answers(), ["heart"]);
+        assert_eq!(game.share_text(SQUARES), "wordlers X/7\n\n⬛ 1");
+        assert_eq!(game.share_text(['⬜', '🟦', '🟧']), "wordlers X/7\n\n⬜ 1");
+
+        // In hard mode, a guess only has to follow one board's hints.
+        let mut game = get_game_with_boards(&["heart", "hoist"]);
+        submit(&mut game, "earth");
+        assert_eq!(game.hard_mode_violation("hoist"), None);
+        assert_eq!(game.hard_mode_violation("heart"), None);
+        assert_eq!(
+            game.hard_mode_violation("sound"),
+            Some(HardModeViolation::InWord {
+                letter: 'E',
+                count: 1
+            })
+        );
+    }
+
+    #[test]
+    fn plays_absurdle() {
+        let settings = Settings {
+            mode: Mode::Absurdle,
+            ..Default::default()
+        };
+        let words = Rc::new(Words::new(5));
+        let mut game = Game::new(&settings, Rc::clone(&words));
+        assert_eq!(game.title(), "wordlers absurdle");
+
+        // Each guess leaves as many answers as possible.
+        submit(&mut game, "crane");
+        let candidates = game.boards[0].candidates.clone().unwrap();
+        let possible: Vec<_> = words.answers().iter().map(String::as_str).collect();
+        let expected = solver::largest_group("crane", &possible).unwrap();
+        assert_eq!(game.feedback(0), [("crane".into(), expected)]);
+        assert!(candidates.len() > 1);
+        assert!(candidates.iter().all(|c| score("crane", c) == expected));
+        assert!(candidates.contains(&game.answers()[0].to_string()));
+
+        // Guessing the answer only wins once it's the last one left.
+        let answer = game.answers()[0].to_string();
+        submit(&mut game, &answer);
+        assert_eq!(game.has_won(), None);
+        assert_ne!(game.answers()[0], answer);
+
+        let restored = Game::restore(&game.save(), words).unwrap();
+        assert_eq!(restored.feedback(0), game.feedback(0));
+        assert_eq!(restored.boards[0].candidates, game.boards[0].candidates);
+    }
+
+    #[test]
+    fn plays_absurdle_on_several_boards() {
+        let settings = Settings {
+            mode: Mode::Absurdle,
+            rows: Settings::MAX_ROWS,
+            boards: 3,
+            ..Default::default()
+        };
+        let words = Rc::new(Words::new(5));
+        let mut game = Game::new(&settings, Rc::clone(&words));
+
+        // Each board dodges into its own group of answers, so they get
+        // different feedback and can't be solved by the same guess.
+        submit(&mut game, "crane");
+        let feedback: HashSet<_> = (0..3).map(|board| game.feedback(board)).collect();
+        assert_eq!(feedback.len(), 3);
+        for (i, board) in game.boards.iter().enumerate() {
+            let candidates = board.candidates.as_ref().unwrap();
+            for other in &game.boards[i + 1..] {
+                let others = other.candidates.as_ref().unwrap();
+                assert!(candidates.iter().all(|c| !others.contains(c)));
+            }
+        }
+
+        // Guessing each board's answer over and over solves them one at a
+        // time.
+        while game.has_won().is_none() {
+            let board = game.boards.iter().find(|b| !b.is_solved()).unwrap();
+            let answer = board.answer.clone();
+            let solved = game.boards.iter().filter(|b| b.is_solved()).count();
+            submit(&mut game, &answer);
+            let now_solved = game.boards.iter().filter(|b| b.is_solved()).count();
+            assert!(now_solved <= solved + 1);
+        }
+        assert_eq!(game.has_won(), Some(true));
+    }
+
+    #[test]
+    fn reports_feedback() {
+        let game = get_game_with_guesses("heart", &["earth", "heist"]);
+        assert_eq!(
+            game.feedback(0),
+            vec![
+                ("earth".into(), score("earth", "heart")),
+                ("heist".into(), score("heist", "heart")),
+            ]
+        );
+    }
+
+    #[test]
+    fn shows_hints() {
+        let game = get_game_with_guesses("heart", &["sound"]);
+        let hint = game.hint().unwrap();
+        assert_eq!(hint.board, 0);
+        assert!(hint.possible.len() > 1);
+        assert!(hint.possible.contains(&"heart"));
+        assert_eq!(hint.suggestions.len(), 3);
+
+        let game = get_game_with_guesses("heart", &["earth", "heist"]);
+        let hint = game.hint().unwrap();
+        assert_eq!(hint.possible, ["heart"]);
+        assert!(hint.suggestions.is_empty());
+
+        let game = get_game_with_guesses("heart", &["heart"]);
+        assert_eq!(game.hint(), None);
+
+        // The first hint scores every guess, not only the answers.
+        let answers = "bills\nfills\nhills\nmills\npills\n";
+        let words = Words::parse(5, Some(answers), None).unwrap();
+        let game = Game::new(&Settings::default(), Rc::new(words));
+        let hint = game.hint().unwrap();
+        assert_eq!(hint.possible.len(), 5);
+        assert_eq!(hint.suggestions[0].word, "bumph");
+        assert_eq!(hint.suggestions[0].bits, 5f64.log2());
+    }
+
+    #[test]
+    fn edits_rows() {
+        let mut game = get_game_with_guesses("heart", &[]);
+        let row = |game: &Game| game.boards[0].rows[0].clone();
+        for c in "hxart".chars() {
+            game.try_accept_letter(c);
+        }
+        assert!(!game.try_accept_letter('s'));
+
+        // Fix a letter in the middle of the row.
+        game.try_move_cursor(CursorMove::Home);
+        assert!(!game.try_move_cursor(CursorMove::Left));
+        game.try_move_cursor(CursorMove::Right);
+        game.try_accept_letter('e');
+        assert_eq!(row(&game).get_final_word().as_deref(), Some("heart"));
+        assert_eq!(row(&game).current_cell, Some(2));
+
+        // Delete clears in place, leaving a gap that End moves past.
+        assert!(game.try_clear_letter());
+        assert!(!game.try_clear_letter());
+        assert_eq!(row(&game).cells[2], Cell::Pending(None));
+        game.try_move_cursor(CursorMove::End);
+        assert_eq!(row(&game).current_cell, Some(5));
+        assert!(!game.try_move_cursor(CursorMove::Right));
+
+        // Gaps survive saving and restoring.
+        game.try_delete_letter();
+        let save = game.save();
+        assert!(save.contains("\npending he.r\n"));
+        let restored = Game::restore(&save, Rc::new(Words::new(5))).unwrap();
+        assert_eq!(row(&restored).cells, row(&game).cells);
+
+        assert_eq!(game.try_submit_guess(), Err(GuessError::Incomplete));
+        assert_eq!(game.current_row, 0);
+    }
+
+    #[test]
+    fn plays_whole_words() {
+        let mut game = get_game_with_boards(&["heart", "sound"]);
+        assert_eq!(
+            game.guess("HEART"),
+            Ok(vec![
+                Some(score("heart", "heart")),
+                Some(score("heart", "sound"))
+            ])
+        );
+        assert_eq!(
+            game.guess("xxxxx"),
+            Err(GuessError::NotInDictionary("xxxxx".into()))
+        );
+        assert_eq!(game.guess("four"), Err(GuessError::WrongLength));
+        assert_eq!(
+            game.guess("HEART"),
+            Err(GuessError::AlreadyGuessed("heart".into()))
+        );
+
+        // Typed letters are replaced.
+        game.try_accept_letter('z');
+        let feedback = game.guess("sound").unwrap();
+        assert_eq!(feedback, [None, Some("GGGGG".parse().unwrap())]);
+        assert_eq!(game.guess("sound"), Err(GuessError::GameOver));
+        assert_eq!(game.try_submit_guess(), Err(GuessError::GameOver));
+        assert_eq!(game.guesses(), ["heart", "sound"]);
+    }
+
     #[test]
     fn handles_multi_letters_correctly() {
         let mut row = get_pending_row_for_str("gucci");
@@ -0,0 +1,183 @@
+//! A screen explaining the rules and controls.
+
+use wordlers::{Game, Mark};
+
+use crate::{view, ColorScheme, Frame, Keys, Options};
+
+/// The rules of the game being played, what the colors mean, and the keys
+/// bound to each action.
+pub struct Help {
+    rules: Vec<String>,
+    mode: Vec<String>,
+    keys: Vec<(String, &'static str)>,
+}
+
+impl Help {
+    /// Width of the drawn help screen.
+    const WIDTH: u16 = 59;
+
+    /// Number of columns of key bindings.
+    const KEY_COLUMNS: usize = 3;
+
+    /// Example cells: a letter, its mark and what that means. Each line must
+    /// fit in `WIDTH` after the cell.
+    const EXAMPLES: [(char, Mark, &'static str); 3] = [
+        ('W', Mark::Correct, "is in the right spot."),
+        ('I', Mark::Present, "is in the word, in another spot."),
+        ('U', Mark::Absent, "is not in the word."),
+    ];
+
+    pub fn new(game: &Game, keys: &Keys) -> Self {
+        let rules = Self::rules(game.length(), game.board_count(), game.rows());
+        Self::with_rules(&rules, &game.describe(), keys)
+    }
+
+    /// Help shown from the menu, before a game is created, for the settings
+    /// chosen there.
+    pub fn for_menu(options: &Options) -> Self {
+        let settings = options.settings();
+        let rules = Self::rules(options.length, settings.boards, settings.rows);
+        let mode = "Choose the settings for a game, then select Play.";
+        Self::with_rules(&rules, mode, &options.keys)
+    }
+
+    fn rules(length: usize, boards: usize, rows: usize) -> String {
+        let goal = match boards {
+            1 => format!("the {length}-letter word"),
+            n => format!("all {n} {length}-letter words"),
+        };
+        format!(
+            "Guess {goal} in {rows} tries. Each guess must be a valid word, and the colors \
+             show how close it came."
+        )
+    }
+
+    fn with_rules(rules: &str, mode: &str, keys: &Keys) -> Self {
+        let wrap = |text: &str| {
+            textwrap::wrap(text, Self::WIDTH as usize)
+                .into_iter()
+                .map(String::from)
+                .collect()
+        };
+
+        Self {
+            rules: wrap(rules),
+            mode: wrap(mode),
+            keys: keys.summary(),
+        }
+    }
+
+    /// Number of rows of key bindings, which are shown in columns.
+    fn key_rows(&self) -> u16 {
+        self.keys.len().div_ceil(Self::KEY_COLUMNS) as u16
+    }
+
+    /// Size (w, h) of the drawn help screen.
+    pub fn size(&self) -> (u16, u16) {
+        let height = 8
+            + self.rules.len()
+            + Self::EXAMPLES.len()
+            + self.key_rows() as usize
+            + self.mode.len();
+        (Self::WIDTH, height as u16)
+    }
+
+    pub fn paint(&self, frame: &mut Frame, top_left: (u16, u16), colors: &ColorScheme) {
+        let (x, mut y) = top_left;
+        let width = Self::WIDTH as usize;
+
+        frame
+            .bg(colors.game_bg)
+            .fg(colors.text_base)
+            .goto(x, y)
+            .print(format_args!("{:^width$}", "HOW TO PLAY"));
+        y += 2;
+
+        for line in &self.rules {
+            frame.goto(x, y).print(line);
+            y += 1;
+        }
+        y += 1;
+
+        for (letter, mark, meaning) in Self::EXAMPLES {
+            view::paint_example_cell(frame, (x, y), colors, letter, mark);
+            frame
+                .bg(colors.game_bg)
+                .fg(colors.text_base)
+                .goto(x + 3, y)
+                .print(format_args!(" {letter} {meaning}"));
+            y += 1;
+        }
+        y += 1;
+
+        frame.goto(x, y).print(format_args!("{:^width$}", "KEYS"));
+        y += 1;
+
+        // Fill each column before moving on to the next.
+        let column = width / Self::KEY_COLUMNS + 1;
+        let rows = self.key_rows() as usize;
+        for (i, (key, action)) in self.keys.iter().enumerate() {
+            let key_x = x + (i / rows * column) as u16;
+            frame
+                .goto(key_x, y + (i % rows) as u16)
+                .fg(colors.text_base)
+                .print(format_args!("{key:<9} "))
+                .fg(colors.text_dim)
+                .print(action);
+        }
+        y += rows as u16 + 1;
+
+        frame.fg(colors.text_base);
+        for line in &self.mode {
+            frame.goto(x, y).print(line);
+            y += 1;
+        }
+        y += 1;
+
+        frame
+            .goto(x, y)
+            .fg(colors.text_dim)
+            .print(format_args!("{:^width$}", "Press any key to continue"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Options, PlainRenderer, Renderer};
+    use std::rc::Rc;
+    use wordlers::Words;
+
+    #[test]
+    fn describes_games() {
+        let options = Options {
+            boards: 4,
+            ..Options::default()
+        };
+        let game = Game::new(&options.settings(), Rc::new(Words::new(5)));
+        let help = Help::new(&game, &options.keys);
+        assert_eq!(
+            help.mode.join(" "),
+            "Random word: 5 letters, 9 guesses, 4 boards"
+        );
+        assert!(help.rules[0].starts_with("Guess all 4 5-letter words in 9 tries."));
+
+        let colors = crate::Theme::Mocha.colors(None);
+        let mut frame = Frame::new(help.size(), colors.text_base, colors.game_bg);
+        help.paint(&mut frame, (1, 1), &colors);
+        let mut text = Vec::new();
+        PlainRenderer.render(&frame, &mut text).unwrap();
+        // The footer is on the last line.
+        let text = String::from_utf8(text).unwrap();
+        assert_eq!(
+            text.lines().last().unwrap().trim(),
+            "Press any key to continue"
+        );
+        assert_eq!(help.size().0, 59);
+
+        // From the menu, the rules are for the chosen settings.
+        let help = Help::for_menu(&options);
+        assert!(help.rules[0].starts_with("Guess all 4 5-letter words in 9 tries."));
+        assert_eq!(help.size(), (59, 21));
+    }
+}
@@ -0,0 +1,320 @@
+//! Key bindings.
+
+use termion::event::Key;
+
+/// Something a key can be bound to. Letters that aren't bound are typed
+/// into the board, or bound only to prompt actions (see `is_prompt`).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Action {
+    Quit,
+    Submit,
+    /// Delete the letter before the cursor.
+    Delete,
+    /// Clear the letter under the cursor.
+    Clear,
+    Left,
+    Right,
+    /// Move up a menu.
+    Up,
+    /// Move down a menu.
+    Down,
+    Home,
+    End,
+    NewGame,
+    Hint,
+    Stats,
+    Theme,
+    Help,
+    /// Copy the result of a finished game to the clipboard.
+    Copy,
+    /// Save the result of a finished game to a file.
+    Save,
+    /// Review a finished game.
+    Review,
+    /// Discard a saved game instead of resuming it.
+    Discard,
+}
+
+impl Action {
+    /// Every action, with its name in config files.
+    const ALL: [(Self, &'static str); 19] = [
+        (Self::Quit, "quit"),
+        (Self::Submit, "submit"),
+        (Self::Delete, "delete"),
+        (Self::Clear, "clear"),
+        (Self::Left, "left"),
+        (Self::Right, "right"),
+        (Self::Up, "up"),
+        (Self::Down, "down"),
+        (Self::Home, "home"),
+        (Self::End, "end"),
+        (Self::NewGame, "new-game"),
+        (Self::Hint, "hint"),
+        (Self::Stats, "stats"),
+        (Self::Theme, "theme"),
+        (Self::Help, "help"),
+        (Self::Copy, "copy"),
+        (Self::Save, "save"),
+        (Self::Review, "review"),
+        (Self::Discard, "discard"),
+    ];
+
+    /// Whether the action only answers a prompt, after a game or before
+    /// resuming one, when nothing is being typed. Only these can be bound to
+    /// letters.
+    pub fn is_prompt(self) -> bool {
+        matches!(self, Self::Copy | Self::Save | Self::Review | Self::Discard)
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|&(action, _)| action)
+    }
+}
+
+/// Which keys trigger each action.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Keys {
+    /// Each bound key, in the order they are described in.
+    bindings: Vec<(Key, Action)>,
+}
+
+impl Default for Keys {
+    fn default() -> Self {
+        Self::preset("default").expect("Default preset exists")
+    }
+}
+
+impl Keys {
+    /// Get a built-in set of bindings by name: `default`, or `vim`, which
+    /// keeps ESC from quitting and adds Alt versions of vim's motions.
+    pub fn preset(name: &str) -> Option<Self> {
+        use Action::*;
+
+        let mut bindings = vec![
+            (Key::Char('\n'), Submit),
+            (Key::Backspace, Delete),
+            // Some terminals send Ctrl-H for backspace.
+            (Key::Ctrl('h'), Delete),
+            (Key::Delete, Clear),
+            (Key::Left, Left),
+            (Key::Right, Right),
+            (Key::Up, Up),
+            (Key::Down, Down),
+            (Key::Home, Home),
+            (Key::End, End),
+            (Key::Ctrl('n'), NewGame),
+            (Key::Char('/'), Hint),
+            (Key::Char('\t'), Stats),
+            (Key::Ctrl('t'), Theme),
+            (Key::Char('?'), Help),
+            (Key::Char('c'), Copy),
+            (Key::Char('s'), Save),
+            (Key::Char('r'), Review),
+            (Key::Char('n'), Discard),
+        ];
+        match name {
+            "default" => bindings.insert(0, (Key::Esc, Quit)),
+            "vim" => bindings.extend([
+                (Key::Ctrl('c'), Quit),
+                (Key::Alt('q'), Quit),
+                (Key::Alt('x'), Clear),
+                (Key::Alt('h'), Left),
+                (Key::Alt('l'), Right),
+                (Key::Alt('k'), Up),
+                (Key::Alt('j'), Down),
+                (Key::Alt('0'), Home),
+                (Key::Alt('^'), Home),
+                (Key::Alt('$'), End),
+            ]),
+            _ => return None,
+        }
+        Some(Self { bindings })
+    }
+
+    /// Get the action bound to a key, if any.
+    pub fn action(&self, key: Key) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|&&(k, _)| k == key)
+            .map(|&(_, action)| action)
+    }
+
+    /// Name the first key bound to an action, for showing in messages.
+    /// Control keys are written the short way, as in `^C`.
+    pub fn describe(&self, action: Action) -> String {
+        match self.bindings.iter().find(|&&(_, a)| a == action) {
+            Some(&(Key::Ctrl(c), _)) => format!("^{}", c.to_ascii_uppercase()),
+            Some(&(key, _)) => key_name(key).to_uppercase(),
+            None => "(unbound)".into(),
+        }
+    }
+
+    /// The first key bound to each action that has one, as described by
+    /// `describe`, along with the action's name.
+    pub fn summary(&self) -> Vec<(String, &'static str)> {
+        Action::ALL
+            .iter()
+            .filter(|&&(action, _)| self.bindings.iter().any(|&(_, a)| a == action))
+            .map(|&(action, name)| (self.describe(action), name))
+            .collect()
+    }
+
+    /// Bind an action to the keys named in `value` (see `parse_key`),
+    /// separated by spaces, instead of the keys it had. The keys are taken
+    /// from any actions they were bound to. Letters can only be bound to
+    /// prompt actions, since they would no longer type into the board.
+    pub fn bind(&mut self, action: &str, value: &str) -> Result<(), String> {
+        let action =
+            Action::from_name(action).ok_or_else(|| format!("unknown action '{action}'"))?;
+        let keys = value
+            .split_whitespace()
+            .map(parse_key)
+            .collect::<Result<Vec<_>, _>>()?;
+        if keys.is_empty() {
+            return Err("expected at least one key".into());
+        }
+        if let Some(&Key::Char(c)) = keys
+            .iter()
+            .find(|key| matches!(key, Key::Char(c) if c.is_ascii_alphabetic()))
+            .filter(|_| !action.is_prompt())
+        {
+            return Err(format!("'{c}' is needed for typing letters"));
+        }
+
+        self.bindings
+            .retain(|(key, a)| *a != action && !keys.contains(key));
+        self.bindings
+            .extend(keys.into_iter().map(|key| (key, action)));
+        Ok(())
+    }
+}
+
+/// Parse a key name such as `esc`, `enter`, `ctrl-h`, `alt-x`, `f1` or a
+/// single character.
+fn parse_key(name: &str) -> Result<Key, String> {
+    let single = |s: &str| {
+        let mut chars = s.chars();
+        chars.next().filter(|_| chars.next().is_none())
+    };
+
+    let key = match name.to_lowercase().as_str() {
+        "esc" => Key::Esc,
+        "enter" => Key::Char('\n'),
+        "tab" => Key::Char('\t'),
+        "space" => Key::Char(' '),
+        "backspace" => Key::Backspace,
+        "delete" => Key::Delete,
+        "insert" => Key::Insert,
+        "left" => Key::Left,
+        "right" => Key::Right,
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        lower => {
+            if let Some(c) = lower.strip_prefix("ctrl-").and_then(single) {
+                Key::Ctrl(c)
+            } else if let Some(c) = lower
+                .strip_prefix("alt-")
+                .and_then(|_| name.get(4..))
+                .and_then(single)
+            {
+                Key::Alt(c)
+            } else if let Some(n) = lower
+                .strip_prefix('f')
+                .and_then(|n| n.parse().ok())
+                .filter(|n| (1..=12).contains(n))
+            {
+                Key::F(n)
+            } else if let Some(c) = single(name) {
+                Key::Char(c)
+            } else {
+                return Err(format!("unknown key '{name}'"));
+            }
+        }
+    };
+    Ok(key)
+}
+
+/// The name of a key, as accepted by `parse_key`.
+fn key_name(key: Key) -> String {
+    match key {
+        Key::Esc => "esc".into(),
+        Key::Char('\n') => "enter".into(),
+        Key::Char('\t') => "tab".into(),
+        Key::Char(' ') => "space".into(),
+        Key::Char(c) => c.into(),
+        Key::Ctrl(c) => format!("ctrl-{c}"),
+        Key::Alt(c) => format!("alt-{c}"),
+        Key::F(n) => format!("f{n}"),
+        other => format!("{other:?}").to_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_keys() {
+        for name in [
+            "esc",
+            "enter",
+            "backspace",
+            "pageup",
+            "ctrl-h",
+            "alt-x",
+            "f12",
+            "/",
+        ] {
+            assert_eq!(key_name(parse_key(name).unwrap()), name);
+        }
+        assert_eq!(parse_key("Ctrl-H"), Ok(Key::Ctrl('h')));
+        assert_eq!(parse_key("alt-X"), Ok(Key::Alt('X')));
+        assert!(parse_key("f13").is_err());
+        assert!(parse_key("ctrl-").is_err());
+    }
+
+    #[test]
+    fn binds_keys() {
+        let mut keys = Keys::default();
+        assert_eq!(keys.action(Key::Esc), Some(Action::Quit));
+        assert_eq!(keys.action(Key::Ctrl('h')), Some(Action::Delete));
+        assert_eq!(keys.action(Key::Char('a')), None);
+        assert_eq!(keys.describe(Action::Submit), "ENTER");
+
+        keys.bind("quit", "ctrl-q f10").unwrap();
+        keys.bind("hint", "f10").unwrap();
+        assert_eq!(keys.action(Key::Esc), None);
+        assert_eq!(keys.action(Key::F(10)), Some(Action::Hint));
+        assert_eq!(keys.describe(Action::Quit), "^Q");
+        assert!(keys.bind("hint", "").is_err());
+        assert!(keys.bind("jump", "j").is_err());
+
+        // Letters are kept for typing.
+        assert_eq!(
+            keys.bind("hint", "f1 e"),
+            Err("'e' is needed for typing letters".into())
+        );
+        assert_eq!(keys.action(Key::Char('e')), None);
+        assert_eq!(keys.action(Key::F(1)), None);
+        keys.bind("hint", "alt-e").unwrap();
+
+        // Except by prompt actions, which aren't used while typing.
+        assert_eq!(keys.action(Key::Char('c')), Some(Action::Copy));
+        keys.bind("copy", "y").unwrap();
+        assert_eq!(keys.action(Key::Char('y')), Some(Action::Copy));
+        assert_eq!(keys.describe(Action::Copy), "Y");
+
+        let vim = Keys::preset("vim").unwrap();
+        assert_eq!(vim.action(Key::Esc), None);
+        assert_eq!(vim.action(Key::Alt('l')), Some(Action::Right));
+        assert_eq!(vim.action(Key::Alt('j')), Some(Action::Down));
+        assert!(Keys::preset("emacs").is_none());
+    }
+}
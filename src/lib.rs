@@ -0,0 +1,44 @@
+//! The wordlers game engine: word lists, scoring, hints and the state of a
+//! game, with nothing tied to a terminal. The `wordlers` binary is one front
+//! end for it; bots and analysis scripts can drive a [`Game`] directly.
+//!
+//! ```
+//! use std::rc::Rc;
+//! use wordlers::{Game, GuessError, Settings, Words};
+//!
+//! let mut game = Game::new(&Settings::default(), Rc::new(Words::new(5)));
+//! let answer = game.answers()[0].to_string();
+//!
+//! // A rejected guess says why, and doesn't use up a row.
+//! assert_eq!(
+//!     game.guess("xxxxx"),
+//!     Err(GuessError::NotInDictionary("xxxxx".into()))
+//! );
+//!
+//! // Each guess gets feedback per board (see `score`).
+//! let feedback = game.guess("crane").unwrap();
+//! assert_eq!(feedback, [Some(wordlers::score("crane", &answer))]);
+//! println!("{}", feedback[0].unwrap()); // Like "..GY."
+//!
+//! if game.has_won().is_none() {
+//!     game.guess(&answer).unwrap();
+//! }
+//! assert_eq!(game.has_won(), Some(true));
+//! ```
+
+mod feedback;
+pub use feedback::{score, Feedback, Mark};
+
+mod game;
+pub use game::{
+    Board, BoardRow, Cell, CursorMove, Game, GuessError, HardModeViolation, Hint, Keyboard,
+    RestoreError,
+};
+
+mod settings;
+pub use settings::{Mode, Settings};
+
+pub mod solver;
+
+mod words;
+pub use words::Words;
@@ -1,5 +1,6 @@
 use std::io;
 use std::thread;
+use std::time::Duration;
 
 use termion::event::Key;
 use termion::input::TermRead;
@@ -9,31 +10,61 @@ use termion::screen::IntoAlternateScreen;
 use tokio::select;
 use tokio::signal::unix::{signal, SignalKind};
 use tokio::sync::mpsc;
+use tokio::time::{self, MissedTickBehavior};
+
+use wordlers::Words;
 
 mod app;
 use app::App;
 
 mod color_scheme;
-pub use color_scheme::ColorScheme;
+pub use color_scheme::{Color, ColorScheme, Palette, Rgb, Theme};
+
+mod help;
+pub use help::Help;
+
+mod keys;
+pub use keys::{Action, Keys};
+
+mod menu;
+pub use menu::{Menu, MenuEvent};
+
+mod options;
+pub use options::{Options, Setting};
+
+mod render;
+#[cfg(feature = "crossterm")]
+pub use render::CrosstermRenderer;
+pub use render::{Frame, Glyph, PlainRenderer, Renderer, TermionRenderer};
+
+mod review;
+pub use review::Review;
 
-mod game;
-pub use game::Game;
+#[cfg(test)]
+mod snapshot;
+
+mod stats;
+pub use stats::{GameRecord, Stats};
+
+mod storage;
+pub use storage::Storage;
 
 mod util;
 
-mod words;
-pub use words::Words;
+mod view;
+pub use view::GameView;
+
+/// Time between frames of an animation.
+const FRAME_INTERVAL: Duration = Duration::from_millis(50);
 
 /// Spawn a thread that sends termion key events asynchronously.
 fn spawn_input_thread() -> mpsc::UnboundedReceiver<Key> {
     let (tx, rx) = mpsc::unbounded_channel();
     thread::spawn(move || {
         let stdin = io::stdin();
-        for key in stdin.keys() {
-            if let Ok(key) = key {
-                if tx.send(key).is_err() {
-                    break;
-                }
+        for key in stdin.keys().flatten() {
+            if tx.send(key).is_err() {
+                break;
             }
         }
     });
@@ -42,22 +73,61 @@ fn spawn_input_thread() -> mpsc::UnboundedReceiver<Key> {
 
 /// Run the game.
 async fn run() -> io::Result<()> {
+    let options = Options::load(std::env::args().skip(1))?;
+    let words = Words::load(
+        options.length,
+        options.answers.as_deref(),
+        options.guesses.as_deref(),
+    )?;
+    let custom_colors = options
+        .colors
+        .as_deref()
+        .map(ColorScheme::load)
+        .transpose()?;
+
     // Set up resize and key event listeners.
     let mut resized_events = signal(SignalKind::window_change())?;
     let mut key_events = spawn_input_thread();
+    let mut frames = time::interval(FRAME_INTERVAL);
+    frames.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
     // Open an "Alternate Screen" that will restore terminal session on drop.
     let screen = io::stdout().into_raw_mode()?.into_alternate_screen()?;
-    let mut app = App::new(screen)?;
+    #[cfg(feature = "crossterm")]
+    let renderer = Box::new(CrosstermRenderer);
+    #[cfg(not(feature = "crossterm"))]
+    let renderer = Box::new(TermionRenderer);
+    let mut app = App::new(
+        screen,
+        options,
+        words,
+        custom_colors,
+        renderer,
+        Storage::open(),
+    )?;
+    app.repaint()?;
 
     loop {
         select! {
             Some(key) = key_events.recv() => {
-                match key {
-                    Key::Esc => break,
-                    k => app.handle_key(k)?,
+                match app.action(key) {
+                    Some(Action::Quit) => {
+                        app.save_progress()?;
+                        break;
+                    }
+                    _ => {
+                        // Give the first frame of a new animation its full time.
+                        let was_animating = app.is_animating();
+                        app.handle_key(key)?;
+                        if !was_animating && app.is_animating() {
+                            frames.reset();
+                        }
+                    }
                 }
             },
+            _ = frames.tick(), if app.is_animating() => {
+                app.tick()?;
+            },
             _ = resized_events.recv() => {
                 app.repaint()?;
             },
@@ -0,0 +1,288 @@
+//! The start screen, where a game's settings are chosen before playing.
+
+use wordlers::{Mode, Words};
+
+use crate::{Action, ColorScheme, Frame, Options, Setting};
+
+/// Something that can be chosen in the menu.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Item {
+    Play,
+    Setting(Setting),
+    Stats,
+}
+
+/// What a key handled by the menu asks the app to do.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MenuEvent {
+    /// Another item was selected.
+    Moved,
+    /// A setting was changed, and should be saved.
+    Changed(Setting),
+    /// Start a game with the chosen settings.
+    Play,
+    /// Show the statistics.
+    Stats,
+}
+
+/// The menu's selected item, and a message to show beneath the items.
+#[derive(Default)]
+pub struct Menu {
+    selected: usize,
+    message: Vec<String>,
+}
+
+impl Menu {
+    /// Width of the drawn menu.
+    const WIDTH: u16 = 39;
+
+    /// Items in the order they are shown.
+    const ITEMS: [Item; 6] = [
+        Item::Play,
+        Item::Setting(Setting::Mode),
+        Item::Setting(Setting::Length),
+        Item::Setting(Setting::Theme),
+        Item::Setting(Setting::HardMode),
+        Item::Stats,
+    ];
+
+    /// Number of lines kept for a message.
+    const MESSAGE_LINES: usize = 2;
+
+    /// Number of lines describing the keys.
+    const FOOTER_LINES: usize = 2;
+
+    /// Show a message beneath the items, until the next key is handled.
+    pub fn set_message(&mut self, message: &str) {
+        self.message = textwrap::wrap(message, Self::WIDTH as usize)
+            .into_iter()
+            .take(Self::MESSAGE_LINES)
+            .map(String::from)
+            .collect();
+    }
+
+    fn selected_item(&self) -> Item {
+        Self::ITEMS[self.selected]
+    }
+
+    /// Handle an action: up and down select an item, left and right change
+    /// the selected setting, and submitting chooses the selected item (or
+    /// changes the setting, like right). Returns `None` if the action does
+    /// nothing here.
+    pub fn handle_action(
+        &mut self,
+        action: Action,
+        options: &mut Options,
+        has_custom_theme: bool,
+    ) -> Option<MenuEvent> {
+        let count = Self::ITEMS.len();
+        let event = match (action, self.selected_item()) {
+            (Action::Up, _) => {
+                self.selected = (self.selected + count - 1) % count;
+                MenuEvent::Moved
+            }
+            (Action::Down, _) => {
+                self.selected = (self.selected + 1) % count;
+                MenuEvent::Moved
+            }
+            (Action::Submit, Item::Play) => MenuEvent::Play,
+            (Action::Submit, Item::Stats) => MenuEvent::Stats,
+            (Action::Left, Item::Setting(setting)) => {
+                change(setting, false, options, has_custom_theme);
+                MenuEvent::Changed(setting)
+            }
+            (Action::Right | Action::Submit, Item::Setting(setting)) => {
+                change(setting, true, options, has_custom_theme);
+                MenuEvent::Changed(setting)
+            }
+            _ => return None,
+        };
+        self.message.clear();
+        Some(event)
+    }
+
+    /// Size (w, h) of the drawn menu.
+    pub fn size(&self) -> (u16, u16) {
+        let height = 4 + Self::ITEMS.len() + Self::MESSAGE_LINES + Self::FOOTER_LINES;
+        (Self::WIDTH, height as u16)
+    }
+
+    /// Draw the menu, showing the current value of each setting.
+    pub fn paint(
+        &self,
+        frame: &mut Frame,
+        top_left: (u16, u16),
+        colors: &ColorScheme,
+        options: &Options,
+    ) {
+        let (x, mut y) = top_left;
+        let width = Self::WIDTH as usize;
+
+        frame
+            .bg(colors.game_bg)
+            .fg(colors.text_base)
+            .goto(x, y)
+            .print(format_args!("{:^width$}", "WORDLERS"));
+        y += 2;
+
+        for (i, &item) in Self::ITEMS.iter().enumerate() {
+            let selected = i == self.selected;
+            let (label, value) = describe(item, options);
+            // The selected setting shows that it can be changed.
+            let value = match value {
+                Some(value) if selected => format!("< {value} >"),
+                Some(value) => format!("  {value}  "),
+                None => String::new(),
+            };
+            let bg = if selected {
+                colors.cell_row_active
+            } else {
+                colors.game_bg
+            };
+            frame
+                .goto(x, y)
+                .bg(bg)
+                .fg(colors.text_base)
+                .print(format_args!(" {label:<18}{value:>19} "));
+            y += 1;
+        }
+        y += 1;
+
+        frame.bg(colors.game_bg);
+        for (i, line) in self.message.iter().enumerate() {
+            frame.goto(x, y + i as u16).print(line);
+        }
+        y += Self::MESSAGE_LINES as u16 + 1;
+
+        let keys = &options.keys;
+        let footer = [
+            format!(
+                "{}/{}: select, {}/{}: change",
+                keys.describe(Action::Up),
+                keys.describe(Action::Down),
+                keys.describe(Action::Left),
+                keys.describe(Action::Right),
+            ),
+            format!(
+                "{}: choose, {}: quit",
+                keys.describe(Action::Submit),
+                keys.describe(Action::Quit),
+            ),
+        ];
+        frame.fg(colors.text_dim);
+        for (i, line) in footer.iter().enumerate() {
+            frame
+                .goto(x, y + i as u16)
+                .print(format_args!("{line:^width$.width$}"));
+        }
+    }
+}
+
+/// Change a setting to its next or previous value, wrapping around.
+fn change(setting: Setting, forward: bool, options: &mut Options, has_custom_theme: bool) {
+    // Step through `count` values, given the index of the current one.
+    let step = |i: usize, count: usize| {
+        if forward {
+            (i + 1) % count
+        } else {
+            (i + count - 1) % count
+        }
+    };
+
+    match setting {
+        Setting::Mode => {
+            let i = Mode::ALL
+                .iter()
+                .position(|&m| m == options.mode)
+                .unwrap_or(0);
+            options.mode = Mode::ALL[step(i, Mode::ALL.len())];
+        }
+        Setting::Length => {
+            let lengths = Words::MAX_LENGTH - Words::MIN_LENGTH + 1;
+            options.length = Words::MIN_LENGTH + step(options.length - Words::MIN_LENGTH, lengths);
+        }
+        Setting::Theme if forward => options.theme = options.theme.next(has_custom_theme),
+        Setting::Theme => options.theme = options.theme.previous(has_custom_theme),
+        Setting::HardMode => options.hard_mode = !options.hard_mode,
+    }
+}
+
+/// The label of an item, and the value of its setting if it has one.
+fn describe(item: Item, options: &Options) -> (&'static str, Option<String>) {
+    match item {
+        Item::Play => ("Play", None),
+        Item::Setting(Setting::Mode) => ("Mode", Some(options.mode.name().into())),
+        Item::Setting(Setting::Length) => ("Word length", Some(options.length.to_string())),
+        Item::Setting(Setting::Theme) => ("Theme", Some(options.theme.name().into())),
+        Item::Setting(Setting::HardMode) => (
+            "Hard mode",
+            Some(if options.hard_mode { "on" } else { "off" }.into()),
+        ),
+        Item::Stats => ("Statistics", None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PlainRenderer, Renderer, Theme};
+
+    #[test]
+    fn changes_settings() {
+        let mut menu = Menu::default();
+        let mut options = Options::default();
+        let mut press = |action| menu.handle_action(action, &mut options, false);
+
+        assert_eq!(press(Action::Submit), Some(MenuEvent::Play));
+        assert_eq!(press(Action::Right), None);
+        assert_eq!(press(Action::Down), Some(MenuEvent::Moved));
+        assert_eq!(
+            press(Action::Right),
+            Some(MenuEvent::Changed(Setting::Mode))
+        );
+        assert_eq!(press(Action::Down), Some(MenuEvent::Moved));
+        assert_eq!(
+            press(Action::Left),
+            Some(MenuEvent::Changed(Setting::Length))
+        );
+        assert_eq!(press(Action::Down), Some(MenuEvent::Moved));
+        assert_eq!(
+            press(Action::Left),
+            Some(MenuEvent::Changed(Setting::Theme))
+        );
+        assert_eq!(press(Action::Down), Some(MenuEvent::Moved));
+        assert_eq!(
+            press(Action::Submit),
+            Some(MenuEvent::Changed(Setting::HardMode))
+        );
+        assert_eq!(press(Action::Hint), None);
+        assert_eq!(press(Action::Down), Some(MenuEvent::Moved));
+        assert_eq!(press(Action::Submit), Some(MenuEvent::Stats));
+        assert_eq!(press(Action::Down), Some(MenuEvent::Moved));
+        assert_eq!(press(Action::Submit), Some(MenuEvent::Play));
+
+        assert_eq!(options.mode, Mode::Daily);
+        assert_eq!(options.length, 4);
+        assert_eq!(options.theme, Theme::Macchiato);
+        assert!(options.hard_mode);
+
+        // Settings wrap around.
+        let mut menu = Menu::default();
+        menu.handle_action(Action::Up, &mut options, false);
+        menu.handle_action(Action::Up, &mut options, false);
+        menu.handle_action(Action::Up, &mut options, false);
+        menu.handle_action(Action::Up, &mut options, false);
+        menu.handle_action(Action::Left, &mut options, false);
+        assert_eq!(options.length, Words::MAX_LENGTH);
+
+        let colors = Theme::Mocha.colors(None);
+        let mut frame = Frame::new(menu.size(), colors.text_base, colors.game_bg);
+        menu.set_message("Couldn't save settings");
+        menu.paint(&mut frame, (1, 1), &colors, &options);
+        let mut screen = Vec::new();
+        PlainRenderer.render(&frame, &mut screen).unwrap();
+        let screen = String::from_utf8(screen).unwrap();
+        assert!(screen.contains("< 11 >"));
+        assert!(screen.contains("Couldn't save settings"));
+    }
+}
@@ -0,0 +1,408 @@
+//! Options from the command line and the config file.
+
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use wordlers::{Mode, Settings, Words};
+
+use crate::{storage, util, Keys, Palette, Theme};
+
+/// A setting that can be chosen in the menu and saved to the config file.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Setting {
+    Mode,
+    Length,
+    Theme,
+    HardMode,
+}
+
+/// Options that control how a game is set up.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Options {
+    /// How the answer is chosen.
+    pub mode: Mode,
+    /// Require every revealed hint to be used in later guesses.
+    pub hard_mode: bool,
+    /// Number of letters in each word.
+    pub length: usize,
+    /// Number of guesses allowed, if not the default for the word length.
+    pub rows: Option<usize>,
+    /// Number of boards played at once, each with its own answer.
+    pub boards: usize,
+    /// Color scheme to start with.
+    pub theme: Theme,
+    /// File to load a custom color scheme from.
+    pub colors: Option<PathBuf>,
+    /// Draw shape cues for hints with any color scheme.
+    pub cues: bool,
+    /// Colors the terminal can show, if not detected from the environment.
+    pub palette: Option<Palette>,
+    /// Animate revealing guesses and rejecting them.
+    pub animations: bool,
+    /// Key bindings.
+    pub keys: Keys,
+    /// File to load answers from instead of the built-in list.
+    pub answers: Option<PathBuf>,
+    /// File to load allowed guesses from instead of the built-in list.
+    pub guesses: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            mode: Mode::default(),
+            hard_mode: false,
+            length: 5,
+            rows: None,
+            boards: 1,
+            theme: Theme::default(),
+            colors: None,
+            cues: false,
+            palette: None,
+            animations: true,
+            keys: Keys::default(),
+            answers: None,
+            guesses: None,
+        }
+    }
+}
+
+impl Options {
+    /// Options that are switched on by a bare command-line flag, rather than
+    /// taking a value.
+    const FLAGS: [&'static str; 5] = ["daily", "absurdle", "hard", "cues", "no-animations"];
+
+    /// Load options from the config file (if there is one), then override
+    /// them with command-line arguments (excluding the program name).
+    pub fn load(args: impl IntoIterator<Item = String>) -> io::Result<Self> {
+        let mut options = Self::default();
+        if let Some(path) = Self::config_path() {
+            match fs::read_to_string(&path) {
+                Ok(config) => options.apply_config(&path, &config)?,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        options.apply_args(args)?;
+        Ok(options)
+    }
+
+    /// Path of the config file, `$XDG_CONFIG_HOME/wordlers/config.toml`.
+    pub fn config_path() -> Option<PathBuf> {
+        storage::xdg_dir("XDG_CONFIG_HOME", ".config").map(|dir| dir.join("config.toml"))
+    }
+
+    /// Apply command-line arguments of the form `--key value`, or `--flag`.
+    fn apply_args(&mut self, args: impl IntoIterator<Item = String>) -> io::Result<()> {
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let key = arg
+                .strip_prefix("--")
+                .ok_or_else(|| invalid_input(format!("unknown argument '{arg}'")))?;
+
+            let value = if Self::FLAGS.contains(&key) {
+                "true".into()
+            } else {
+                args.next()
+                    .ok_or_else(|| invalid_input(format!("missing value for '{arg}'")))?
+            };
+
+            self.set(key, &value)
+                .map_err(|e| invalid_input(format!("'{arg}': {e}")))?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply a config file made of `key = value` lines. Blank lines and lines
+    /// starting with `#` are ignored, and values may be quoted.
+    fn apply_config(&mut self, path: &Path, config: &str) -> io::Result<()> {
+        for (line, entry) in util::config_entries(config) {
+            entry
+                .and_then(|(key, value)| self.set(key, value))
+                .map_err(|e| invalid_input(format!("{}:{line}: {e}", path.display())))?;
+        }
+
+        Ok(())
+    }
+
+    /// Set a single option by name.
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "daily" => {
+                self.mode = if util::parse_bool(value)? {
+                    Mode::Daily
+                } else {
+                    Mode::Random
+                }
+            }
+            "absurdle" => {
+                self.mode = if util::parse_bool(value)? {
+                    Mode::Absurdle
+                } else {
+                    Mode::Random
+                }
+            }
+            "mode" => {
+                self.mode = Mode::from_name(value)
+                    .ok_or_else(|| format!("expected random, daily or absurdle, not '{value}'"))?
+            }
+            "hard" => self.hard_mode = util::parse_bool(value)?,
+            "length" => self.length = parse_number(value, Words::MIN_LENGTH..=Words::MAX_LENGTH)?,
+            "rows" => self.rows = Some(parse_number(value, 1..=Settings::MAX_ROWS)?),
+            "boards" => self.boards = parse_number(value, 1..=Settings::MAX_BOARDS)?,
+            "theme" => {
+                self.theme = Theme::from_name(value).ok_or_else(|| {
+                    format!(
+                        "expected latte, frappe, macchiato, mocha or high-contrast, not '{value}'"
+                    )
+                })?
+            }
+            "colors" => {
+                // A custom color scheme is used unless a theme is chosen later.
+                self.colors = Some(value.into());
+                self.theme = Theme::Custom;
+            }
+            "cues" => self.cues = util::parse_bool(value)?,
+            "palette" => {
+                self.palette =
+                    Some(Palette::from_name(value).ok_or_else(|| {
+                        format!("expected truecolor, 256, 16 or mono, not '{value}'")
+                    })?)
+            }
+            "no-animations" => self.animations = !util::parse_bool(value)?,
+            // Choosing a preset replaces any bindings set before it.
+            "keys" => {
+                self.keys = Keys::preset(value)
+                    .ok_or_else(|| format!("expected default or vim, not '{value}'"))?
+            }
+            _ if key.starts_with("key.") => self.keys.bind(&key[4..], value)?,
+            "answers" => self.answers = Some(value.into()),
+            "guesses" => self.guesses = Some(value.into()),
+            _ => return Err(format!("unknown option '{key}'")),
+        }
+        Ok(())
+    }
+
+    /// Save a setting chosen in the menu to the config file, leaving the
+    /// rest of the file as it was. Other options given on the command line
+    /// aren't saved.
+    pub fn save_setting(&self, setting: Setting) -> io::Result<()> {
+        let Some(path) = Self::config_path() else {
+            return Ok(());
+        };
+        let config = match fs::read_to_string(&path) {
+            Ok(config) => config,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, self.update_config(&config, setting))
+    }
+
+    /// Rewrite a config file with the current value of a setting. The older
+    /// `daily` and `absurdle` options are replaced by `mode`, and the theme
+    /// is moved to the end so that it takes precedence over `colors`, or
+    /// removed to let `colors` choose the custom theme.
+    fn update_config(&self, config: &str, setting: Setting) -> String {
+        let (settings, removed) = match setting {
+            Setting::Mode => (
+                vec![("mode", format!("\"{}\"", self.mode.name()))],
+                &["daily", "absurdle"][..],
+            ),
+            Setting::Length => (vec![("length", self.length.to_string())], &[][..]),
+            Setting::HardMode => (vec![("hard", self.hard_mode.to_string())], &[][..]),
+            Setting::Theme if self.theme == Theme::Custom => (vec![], &["theme"][..]),
+            Setting::Theme => (
+                vec![("theme", format!("\"{}\"", self.theme.name()))],
+                &["theme"][..],
+            ),
+        };
+        util::update_config(config, &settings, removed)
+    }
+
+    /// Number of guesses allowed, if not the usual number (see
+    /// `Settings::default_rows`).
+    pub fn rows(&self) -> usize {
+        self.rows
+            .unwrap_or_else(|| Settings::default_rows(self.length, self.boards))
+    }
+
+    /// Settings for a new game.
+    pub fn settings(&self) -> Settings {
+        Settings {
+            mode: self.mode,
+            hard_mode: self.hard_mode,
+            rows: self.rows(),
+            boards: self.boards,
+        }
+    }
+}
+
+fn invalid_input(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+/// Parse a number, which must be within `range`.
+fn parse_number(value: &str, range: RangeInclusive<usize>) -> Result<usize, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|n| range.contains(n))
+        .ok_or_else(|| {
+            format!(
+                "expected a number from {} to {}, not '{value}'",
+                range.start(),
+                range.end()
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> io::Result<Options> {
+        let mut options = Options::default();
+        options.apply_args(args.iter().map(|a| a.to_string()))?;
+        Ok(options)
+    }
+
+    #[test]
+    fn parses_args() {
+        assert_eq!(parse(&[]).unwrap(), Options::default());
+        assert!(parse(&["--hard"]).unwrap().hard_mode);
+        assert!(!parse(&["--no-animations"]).unwrap().animations);
+        assert_eq!(parse(&["--daily"]).unwrap().mode, Mode::Daily);
+        assert_eq!(parse(&["--absurdle"]).unwrap().mode, Mode::Absurdle);
+        assert!(parse(&["--bogus"]).is_err());
+        assert!(parse(&["bogus"]).is_err());
+
+        let options = parse(&["--length", "7"]).unwrap();
+        assert_eq!((options.length, options.rows()), (7, 8));
+        let options = parse(&["--length", "4", "--rows", "3"]).unwrap();
+        assert_eq!((options.length, options.rows()), (4, 3));
+        assert_eq!(Options::default().rows(), 6);
+
+        let options = parse(&["--boards", "4"]).unwrap();
+        assert_eq!((options.boards, options.rows()), (4, 9));
+        assert!(parse(&["--boards", "9"]).is_err());
+
+        assert_eq!(parse(&["--theme", "latte"]).unwrap().theme, Theme::Latte);
+        assert!(parse(&["--theme", "dracula"]).is_err());
+        assert_eq!(
+            parse(&["--palette", "256"]).unwrap().palette,
+            Some(Palette::Ansi256)
+        );
+        assert!(parse(&["--palette", "8"]).is_err());
+        let options = parse(&["--colors", "dark.toml"]).unwrap();
+        assert_eq!(options.theme, Theme::Custom);
+        assert_eq!(options.colors, Some("dark.toml".into()));
+
+        assert!(parse(&["--length"]).is_err());
+        assert!(parse(&["--length", "12"]).is_err());
+        assert!(parse(&["--rows", "zero"]).is_err());
+
+        let options = parse(&["--answers", "a.txt", "--guesses", "g.txt"]).unwrap();
+        assert_eq!(options.answers, Some("a.txt".into()));
+        assert_eq!(options.guesses, Some("g.txt".into()));
+    }
+
+    #[test]
+    fn parses_config() {
+        let config = "# Game night settings\n\
+                      length = 6\n\
+                      hard = true\n\
+                      \n\
+                      answers = \"/home/me/jargon.txt\"\n\
+                      keys = vim\n\
+                      key.hint = \"f1 ?\"\n";
+        let mut options = Options::default();
+        options
+            .apply_config(Path::new("config.toml"), config)
+            .unwrap();
+        assert_eq!(options.length, 6);
+        assert!(options.hard_mode);
+        assert_eq!(options.answers, Some("/home/me/jargon.txt".into()));
+        let mut keys = Keys::preset("vim").unwrap();
+        keys.bind("hint", "f1 ?").unwrap();
+        assert_eq!(options.keys, keys);
+
+        // Command-line arguments override the config file.
+        options.apply_args(["--length".into(), "7".into()]).unwrap();
+        assert_eq!(options.length, 7);
+
+        let err = options
+            .apply_config(Path::new("config.toml"), "length = 6\nhard = yes\n")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "config.toml:2: expected 'true' or 'false', not 'yes'"
+        );
+        assert!(options
+            .apply_config(Path::new("config.toml"), "length\n")
+            .is_err());
+    }
+
+    #[test]
+    fn updates_config() {
+        let options = Options {
+            mode: Mode::Absurdle,
+            length: 6,
+            hard_mode: true,
+            theme: Theme::Latte,
+            ..Options::default()
+        };
+        let config = "# Game night settings\n\
+                      theme = mocha\n\
+                      length = 7\n\
+                      daily = true\n\
+                      colors = \"dark.toml\"\n";
+
+        // Only the setting that changed is written.
+        let updated = options.update_config(config, Setting::Mode);
+        assert_eq!(
+            updated,
+            "# Game night settings\n\
+             theme = mocha\n\
+             length = 7\n\
+             colors = \"dark.toml\"\n\
+             mode = \"absurdle\"\n"
+        );
+        let updated = options.update_config(&updated, Setting::Length);
+        assert_eq!(updated.lines().nth(2), Some("length = 6"));
+        let updated = options.update_config(&updated, Setting::Theme);
+        assert_eq!(
+            updated,
+            "# Game night settings\n\
+             length = 6\n\
+             colors = \"dark.toml\"\n\
+             mode = \"absurdle\"\n\
+             theme = \"latte\"\n"
+        );
+
+        let mut loaded = Options::default();
+        loaded
+            .apply_config(Path::new("config.toml"), &updated)
+            .unwrap();
+        assert_eq!(
+            (loaded.mode, loaded.length, loaded.hard_mode, loaded.theme),
+            (Mode::Absurdle, 6, false, Theme::Latte)
+        );
+        assert_eq!(options.update_config(&updated, Setting::Theme), updated);
+
+        // Choosing the custom theme leaves it to `colors`.
+        let custom = Options {
+            theme: Theme::Custom,
+            ..Options::default()
+        };
+        assert!(!custom
+            .update_config(&updated, Setting::Theme)
+            .contains("theme"));
+    }
+}
@@ -0,0 +1,291 @@
+//! Drawing screens into frames of styled characters, and writing frames out.
+//!
+//! Screens are painted into a `Frame` without knowing how it will be shown.
+//! A `Renderer` then writes the frame out, as escape codes for a terminal or
+//! as plain text.
+
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use crate::Color;
+
+/// A character, and the colors it's drawn in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Glyph {
+    pub ch: char,
+    pub fg: Color,
+    pub bg: Color,
+}
+
+/// A screen's worth of glyphs.
+///
+/// Text is printed like on a terminal: at a pen position, in the pen's
+/// colors. Unlike a terminal, text that runs off the edge is dropped.
+pub struct Frame {
+    size: (u16, u16),
+    glyphs: Vec<Glyph>,
+    /// Position (x, y) of the next glyph, counting from (1, 1).
+    pen: (u16, u16),
+    fg: Color,
+    bg: Color,
+}
+
+impl Frame {
+    /// A blank frame of `size` (w, h), all `bg`. The pen starts at the top
+    /// left, drawing `fg` on `bg`.
+    pub fn new(size: (u16, u16), fg: Color, bg: Color) -> Self {
+        let blank = Glyph { ch: ' ', fg, bg };
+        Self {
+            size,
+            glyphs: vec![blank; size.0 as usize * size.1 as usize],
+            pen: (1, 1),
+            fg,
+            bg,
+        }
+    }
+
+    /// Size (w, h) of the frame.
+    pub fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    /// Move the pen to (x, y), counting from (1, 1) at the top left as
+    /// terminals do.
+    pub fn goto(&mut self, x: u16, y: u16) -> &mut Self {
+        self.pen = (x, y);
+        self
+    }
+
+    /// Set the color of the text printed next.
+    pub fn fg(&mut self, color: Color) -> &mut Self {
+        self.fg = color;
+        self
+    }
+
+    /// Set the color behind the text printed next.
+    pub fn bg(&mut self, color: Color) -> &mut Self {
+        self.bg = color;
+        self
+    }
+
+    /// Print text at the pen, moving it along. Every character takes one
+    /// cell, so text must not contain newlines or wide characters.
+    pub fn print(&mut self, text: impl Display) -> &mut Self {
+        for ch in text.to_string().chars() {
+            let (x, y) = self.pen;
+            if let Some(i) = self.index(x, y) {
+                self.glyphs[i] = Glyph {
+                    ch,
+                    fg: self.fg,
+                    bg: self.bg,
+                };
+            }
+            self.pen.0 = x.saturating_add(1);
+        }
+        self
+    }
+
+    /// The glyph at (x, y), or `None` if that's off the frame.
+    pub fn get(&self, x: u16, y: u16) -> Option<Glyph> {
+        self.index(x, y).map(|i| self.glyphs[i])
+    }
+
+    /// Each row of glyphs, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[Glyph]> {
+        self.glyphs.chunks(self.size.0.max(1) as usize)
+    }
+
+    fn index(&self, x: u16, y: u16) -> Option<usize> {
+        let (w, h) = self.size;
+        let in_frame = (1..=w).contains(&x) && (1..=h).contains(&y);
+        in_frame.then(|| (y - 1) as usize * w as usize + (x - 1) as usize)
+    }
+}
+
+/// Writes frames out.
+pub trait Renderer {
+    /// Write a whole frame, replacing whatever was written before.
+    fn render(&mut self, frame: &Frame, out: &mut dyn Write) -> io::Result<()>;
+}
+
+/// Writes frames to a terminal as escape codes, using termion.
+pub struct TermionRenderer;
+
+impl Renderer for TermionRenderer {
+    fn render(&mut self, frame: &Frame, out: &mut dyn Write) -> io::Result<()> {
+        let mut colors = None;
+        for (y, row) in frame.rows().enumerate() {
+            write!(out, "{}", termion::cursor::Goto(1, y as u16 + 1))?;
+            for glyph in row {
+                // The background goes first, since a monochrome one resets
+                // any attributes set for the text before it.
+                if colors != Some((glyph.fg, glyph.bg)) {
+                    write!(
+                        out,
+                        "{}{}",
+                        termion::color::Bg(glyph.bg),
+                        termion::color::Fg(glyph.fg)
+                    )?;
+                    colors = Some((glyph.fg, glyph.bg));
+                }
+                write!(out, "{}", glyph.ch)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes frames to a terminal using crossterm's styling commands instead
+/// of termion's escape codes. Input, the terminal size and signals still
+/// go through termion, so this doesn't make wordlers build on Windows.
+#[cfg(feature = "crossterm")]
+pub struct CrosstermRenderer;
+
+#[cfg(feature = "crossterm")]
+impl CrosstermRenderer {
+    /// Start drawing in the given colors. Monochrome colors are sets of
+    /// attributes, and drawing on one first clears any set before.
+    fn set_colors(out: &mut impl Write, fg: Color, bg: Color) -> io::Result<()> {
+        use crossterm::style::{Attribute, SetAttribute, SetBackgroundColor, SetForegroundColor};
+
+        let attributes = |params: &str| -> Vec<Attribute> {
+            params
+                .split(';')
+                .filter_map(|param| match param {
+                    "1" => Some(Attribute::Bold),
+                    "2" => Some(Attribute::Dim),
+                    "4" => Some(Attribute::Underlined),
+                    "7" => Some(Attribute::Reverse),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        match bg {
+            Color::Mono { bg, .. } => {
+                crossterm::queue!(out, SetAttribute(Attribute::Reset))?;
+                for attribute in attributes(bg) {
+                    crossterm::queue!(out, SetAttribute(attribute))?;
+                }
+            }
+            color => crossterm::queue!(out, SetBackgroundColor(Self::color(color)))?,
+        }
+        match fg {
+            Color::Mono { fg, .. } => {
+                for attribute in attributes(fg) {
+                    crossterm::queue!(out, SetAttribute(attribute))?;
+                }
+            }
+            color => crossterm::queue!(out, SetForegroundColor(Self::color(color)))?,
+        }
+        Ok(())
+    }
+
+    /// The crossterm color for one of ours. Monochrome colors have none.
+    fn color(color: Color) -> crossterm::style::Color {
+        use crate::Rgb;
+        use crossterm::style::Color as C;
+
+        const ANSI16: [C; 16] = [
+            C::Black,
+            C::DarkRed,
+            C::DarkGreen,
+            C::DarkYellow,
+            C::DarkBlue,
+            C::DarkMagenta,
+            C::DarkCyan,
+            C::Grey,
+            C::DarkGrey,
+            C::Red,
+            C::Green,
+            C::Yellow,
+            C::Blue,
+            C::Magenta,
+            C::Cyan,
+            C::White,
+        ];
+
+        match color {
+            Color::Rgb(Rgb(r, g, b)) => C::Rgb { r, g, b },
+            Color::Ansi256(n) => C::AnsiValue(n),
+            Color::Ansi16(n) => ANSI16[n as usize & 15],
+            Color::Mono { .. } => C::Reset,
+        }
+    }
+}
+
+#[cfg(feature = "crossterm")]
+impl Renderer for CrosstermRenderer {
+    fn render(&mut self, frame: &Frame, mut out: &mut dyn Write) -> io::Result<()> {
+        use crossterm::{cursor::MoveTo, style::Print};
+
+        let mut colors = None;
+        for (y, row) in frame.rows().enumerate() {
+            crossterm::queue!(&mut out, MoveTo(0, y as u16))?;
+            for glyph in row {
+                if colors != Some((glyph.fg, glyph.bg)) {
+                    Self::set_colors(&mut out, glyph.fg, glyph.bg)?;
+                    colors = Some((glyph.fg, glyph.bg));
+                }
+                crossterm::queue!(&mut out, Print(glyph.ch))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes frames as plain text, a line per row with trailing spaces
+/// removed, for logs and tests.
+pub struct PlainRenderer;
+
+impl Renderer for PlainRenderer {
+    fn render(&mut self, frame: &Frame, out: &mut dyn Write) -> io::Result<()> {
+        for row in frame.rows() {
+            let line: String = row.iter().map(|glyph| glyph.ch).collect();
+            writeln!(out, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Color = Color::Ansi16(0);
+    const RED: Color = Color::Ansi16(1);
+    const WHITE: Color = Color::Ansi16(15);
+
+    #[test]
+    fn prints_into_frames() {
+        let mut frame = Frame::new((6, 2), WHITE, BLACK);
+        frame.goto(2, 1).print("ab").fg(RED).bg(WHITE).print('c');
+        // Text off the edge is dropped.
+        frame.goto(5, 2).print("xyz").goto(0, 1).print("?");
+
+        let glyph = |ch, fg, bg| Some(Glyph { ch, fg, bg });
+        assert_eq!(frame.get(1, 1), glyph(' ', WHITE, BLACK));
+        assert_eq!(frame.get(3, 1), glyph('b', WHITE, BLACK));
+        assert_eq!(frame.get(4, 1), glyph('c', RED, WHITE));
+        assert_eq!(frame.get(6, 2), glyph('y', RED, WHITE));
+        assert_eq!(frame.get(7, 2), None);
+
+        let mut text = Vec::new();
+        PlainRenderer.render(&frame, &mut text).unwrap();
+        assert_eq!(String::from_utf8(text).unwrap(), " abc\n    xy\n");
+    }
+
+    #[test]
+    fn renders_with_termion() {
+        let mut frame = Frame::new((3, 1), WHITE, BLACK);
+        frame.goto(2, 1).fg(RED).print("ab");
+
+        let mut out = Vec::new();
+        TermionRenderer.render(&frame, &mut out).unwrap();
+        // Colors are only written when they change.
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[1;1H\x1b[40m\x1b[97m \x1b[40m\x1b[31mab"
+        );
+    }
+}
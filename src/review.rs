@@ -0,0 +1,178 @@
+//! Post-game analysis comparing each guess against the solver's best.
+
+use wordlers::{solver, Feedback, Words};
+
+use crate::{ColorScheme, Frame};
+
+/// Analysis of a single guess.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReviewStep {
+    pub guess: String,
+    /// Number of possible answers before the guess.
+    pub before: usize,
+    /// Number of possible answers after the guess.
+    pub after: usize,
+    /// Information the guess was expected to gain, in bits.
+    pub expected: f64,
+    /// The best of all allowed guesses at this point, and its expected
+    /// information.
+    pub best: Option<(String, f64)>,
+}
+
+impl ReviewStep {
+    /// Information actually gained by the guess, in bits.
+    pub fn bits(&self) -> f64 {
+        if self.after == 0 {
+            0.0
+        } else {
+            (self.before as f64 / self.after as f64).log2()
+        }
+    }
+}
+
+/// A guess-by-guess review of a finished game.
+#[derive(Clone)]
+pub struct Review {
+    steps: Vec<ReviewStep>,
+    length: usize,
+}
+
+impl Review {
+    /// Analyze a game given each guess and the feedback it got.
+    pub fn new(words: &Words, feedback: &[(String, Feedback)]) -> Self {
+        let answers = || words.answers().iter().map(String::as_str);
+        let mut candidates: Vec<_> = words.guesses().collect();
+        candidates.sort_unstable();
+
+        let steps = (0..feedback.len())
+            .map(|i| {
+                let possible = solver::possible_answers(answers(), &feedback[..i]);
+                let after = solver::possible_answers(possible.iter().copied(), &feedback[i..=i]);
+                let guess = &feedback[i].0;
+                let expected = solver::expected_information(guess, &possible);
+
+                // Every candidate is scored, unlike for hints, since a review
+                // is only made once per game. The first step depends only on
+                // the word lists, so it's worked out once and kept.
+                let best = if i == 0 {
+                    words.opening_guesses().first().cloned()
+                } else {
+                    solver::best_guesses(candidates.iter().copied(), &possible, 1)
+                        .pop()
+                        .map(|s| (s.word.to_string(), s.bits))
+                };
+
+                ReviewStep {
+                    guess: guess.clone(),
+                    before: possible.len(),
+                    after: after.len(),
+                    expected,
+                    best,
+                }
+            })
+            .collect();
+
+        Self {
+            steps,
+            length: feedback.first().map_or(0, |(guess, _)| guess.len()),
+        }
+    }
+
+    /// Width of a word column, which fits its heading.
+    fn word_width(&self) -> usize {
+        self.length.max(5)
+    }
+
+    /// Size (w, h) of the drawn review screen.
+    pub fn size(&self) -> (u16, u16) {
+        (
+            (self.word_width() * 2 + 35) as u16,
+            5 + self.steps.len() as u16,
+        )
+    }
+
+    pub fn paint(&self, frame: &mut Frame, top_left: (u16, u16), colors: &ColorScheme) {
+        let (x, y) = top_left;
+        let (width, height) = self.size();
+        let (width, w) = (width as usize, self.word_width());
+
+        frame
+            .bg(colors.game_bg)
+            .fg(colors.text_base)
+            .goto(x, y)
+            .print(format_args!("{:^width$}", "REVIEW"))
+            .goto(x, y + 2)
+            .fg(colors.text_dim)
+            .print(format_args!(
+                "{:<w$} {:>6} {:>6} {:>5} {:>5}   {:<w$} {:>5}",
+                "Guess", "Before", "After", "Bits", "Exp.", "Best", "Exp.",
+            ));
+
+        for (i, step) in self.steps.iter().enumerate() {
+            let (best, best_bits) = match &step.best {
+                Some((word, bits)) => (word.to_ascii_uppercase(), format!("{bits:.2}")),
+                None => (String::new(), String::new()),
+            };
+            // Color the guess by how close it came to the best expectation.
+            let guess_color = match &step.best {
+                Some((_, bits)) if step.expected >= bits - 0.005 => colors.cell_correct,
+                Some((_, bits)) if step.expected >= bits * 0.75 => colors.cell_in_word,
+                _ => colors.text_base,
+            };
+
+            frame
+                .goto(x, y + 3 + i as u16)
+                .fg(guess_color)
+                .print(format_args!("{:<w$}", step.guess.to_ascii_uppercase()))
+                .fg(colors.text_base)
+                .print(format_args!(
+                    " {:>6} {:>6} {:>5.2} {:>5.2}   {:<w$} {:>5}",
+                    step.before,
+                    step.after,
+                    step.bits(),
+                    step.expected,
+                    best,
+                    best_bits,
+                ));
+        }
+
+        frame
+            .goto(x, y + height - 1)
+            .fg(colors.text_dim)
+            .print(format_args!("{:^width$}", "Press any key to continue"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wordlers::score;
+
+    #[test]
+    fn reviews_guesses() {
+        // A short answer list keeps scoring every guess quick.
+        let answers = "heart\nearth\nhater\nsound\ncrane\nslate\nplumb\n";
+        let words = Words::parse(5, Some(answers), None).unwrap();
+        let feedback: Vec<_> = ["sound", "earth", "heart"]
+            .iter()
+            .map(|guess| (guess.to_string(), score(guess, "heart")))
+            .collect();
+        let review = Review::new(&words, &feedback);
+        let steps = &review.steps;
+
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[0].before, words.answers().len());
+        assert_eq!(steps[0].after, steps[1].before);
+        assert_eq!(steps[1].after, steps[2].before);
+        assert_eq!(steps[2].after, 1);
+        assert!(steps[0].bits() > 0.0);
+
+        // The best guess beats every other allowed guess.
+        let answers: Vec<_> = words.answers().iter().map(String::as_str).collect();
+        let possible = solver::possible_answers(answers, &feedback[..1]);
+        let (_, best_bits) = steps[1].best.clone().unwrap();
+        for guess in words.guesses() {
+            assert!(solver::expected_information(guess, &possible) <= best_bits + 1e-9);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! How a game is set up.
+
+/// How the answer for a game is chosen.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Mode {
+    /// A new random answer every game.
+    #[default]
+    Random,
+    /// One shared answer per calendar day.
+    Daily,
+    /// No answer is fixed: each guess gets whichever pattern leaves the most
+    /// answers possible, as in Absurdle.
+    Absurdle,
+}
+
+impl Mode {
+    /// Every mode.
+    pub const ALL: [Self; 3] = [Self::Random, Self::Daily, Self::Absurdle];
+
+    /// Get a mode by name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    /// The name of a mode, as accepted by `from_name`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Random => "random",
+            Self::Daily => "daily",
+            Self::Absurdle => "absurdle",
+        }
+    }
+}
+
+/// Settings for a new game. The word length is given by the word lists
+/// it's played with.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Settings {
+    /// How the answer is chosen.
+    pub mode: Mode,
+    /// Require every revealed hint to be used in later guesses.
+    pub hard_mode: bool,
+    /// Number of guesses allowed.
+    pub rows: usize,
+    /// Number of boards played at once, each with its own answer.
+    pub boards: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            mode: Mode::default(),
+            hard_mode: false,
+            rows: 6,
+            boards: 1,
+        }
+    }
+}
+
+impl Settings {
+    /// Most guesses that can be allowed.
+    pub const MAX_ROWS: usize = 20;
+
+    /// Most boards that can be played at once.
+    pub const MAX_BOARDS: usize = 8;
+
+    /// The usual number of guesses: 6, or one more than the word length for
+    /// longer words, plus one for each extra board.
+    pub fn default_rows(length: usize, boards: usize) -> usize {
+        6.max(length + 1) + boards - 1
+    }
+}
@@ -0,0 +1,294 @@
+//! Snapshot tests of whole screens.
+//!
+//! A `Terminal` stands in for a real one: the app writes to it, and it plays
+//! back the escape codes written to find the characters and colors left on
+//! screen. Screens are compared to snapshots kept in `snapshots/`. After
+//! changing how something is drawn, run the tests with `UPDATE_SNAPSHOTS=1`
+//! to rewrite them, and check the differences before committing.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// How a character is drawn, as SGR parameters. A color is `None` if it
+/// was never set or was reset.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+struct Style {
+    fg: Option<String>,
+    bg: Option<String>,
+    /// Attributes such as bold (1) or reverse (7), in order.
+    attributes: Vec<u16>,
+}
+
+impl Style {
+    /// Apply the parameters of an SGR (`ESC [ ... m`) sequence.
+    fn apply(&mut self, params: &str) {
+        let mut params = params.split(';');
+        while let Some(param) = params.next() {
+            match param.parse().unwrap_or(0) {
+                0 => *self = Self::default(),
+                n @ (1..=9) => {
+                    if let Err(i) = self.attributes.binary_search(&n) {
+                        self.attributes.insert(i, n);
+                    }
+                }
+                n @ (30..=37 | 90..=97) => self.fg = Some(n.to_string()),
+                n @ (40..=47 | 100..=107) => self.bg = Some(n.to_string()),
+                39 => self.fg = None,
+                49 => self.bg = None,
+                // Extended colors take two more parameters for a 256-color
+                // index, or four for RGB.
+                n @ (38 | 48) => {
+                    let (kind, count) = match params.next() {
+                        Some("5") => ("5", 1),
+                        Some("2") => ("2", 3),
+                        _ => continue,
+                    };
+                    let values: Vec<_> = params.by_ref().take(count).collect();
+                    let color = Some(format!("{n};{kind};{}", values.join(";")));
+                    if n == 38 {
+                        self.fg = color;
+                    } else {
+                        self.bg = color;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(fg) = &self.fg {
+            parts.push(format!("fg {fg}"));
+        }
+        if let Some(bg) = &self.bg {
+            parts.push(format!("bg {bg}"));
+        }
+        if !self.attributes.is_empty() {
+            let attributes: Vec<_> = self.attributes.iter().map(u16::to_string).collect();
+            parts.push(format!("attributes {}", attributes.join(";")));
+        }
+        match &parts[..] {
+            [] => write!(f, "default"),
+            _ => write!(f, "{}", parts.join(", ")),
+        }
+    }
+}
+
+/// A terminal of a fixed size, keeping everything written to it.
+pub struct Terminal {
+    size: (u16, u16),
+    output: Vec<u8>,
+}
+
+impl Terminal {
+    pub fn new(size: (u16, u16)) -> Self {
+        Self {
+            size,
+            output: Vec::new(),
+        }
+    }
+
+    /// Size (w, h) of the screen.
+    pub fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    /// Play back everything written so far, giving the screen it leaves.
+    ///
+    /// Only what the app writes is understood: moving the cursor, setting
+    /// colors and attributes, and printing. Text past the right edge is
+    /// dropped rather than wrapped, and other escape codes are ignored.
+    pub fn screen(&self) -> Screen {
+        let (w, h) = (self.size.0 as usize, self.size.1 as usize);
+        let mut screen = Screen {
+            width: w,
+            cells: vec![(' ', Style::default()); w * h],
+        };
+        let mut style = Style::default();
+        let (mut x, mut y) = (0, 0);
+
+        let output = String::from_utf8_lossy(&self.output);
+        let mut chars = output.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\x1b' => match chars.next() {
+                    Some('[') => {
+                        // Parameters run up to a final byte in '@'..='~'.
+                        let mut params = String::new();
+                        for c in chars.by_ref() {
+                            if ('@'..='~').contains(&c) {
+                                match c {
+                                    'H' => {
+                                        let mut position = params
+                                            .split(';')
+                                            .map(|n| n.parse::<usize>().unwrap_or(1).max(1) - 1);
+                                        y = position.next().unwrap_or(0);
+                                        x = position.next().unwrap_or(0);
+                                    }
+                                    'm' => style.apply(&params),
+                                    _ => {}
+                                }
+                                break;
+                            }
+                            params.push(c);
+                        }
+                    }
+                    // Operating system commands, like copying to the
+                    // clipboard, run up to a BEL.
+                    Some(']') => {
+                        chars.by_ref().find(|&c| c == '\x07');
+                    }
+                    _ => {}
+                },
+                '\r' => x = 0,
+                '\n' => y += 1,
+                c => {
+                    if x < w && y < h {
+                        screen.cells[y * w + x] = (c, style.clone());
+                    }
+                    x += 1;
+                }
+            }
+        }
+        screen
+    }
+}
+
+impl Write for Terminal {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The characters on a terminal's screen, and how each is drawn.
+///
+/// It's written as the text on screen with trailing spaces removed, then a
+/// map with a key for the style of each character, then what each key
+/// stands for.
+pub struct Screen {
+    width: usize,
+    cells: Vec<(char, Style)>,
+}
+
+impl Screen {
+    /// Keys standing for styles in the written map, in order of use.
+    const KEYS: &'static str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+    /// The text on screen, a line per row with trailing spaces removed.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for row in self.cells.chunks(self.width.max(1)) {
+            let line: String = row.iter().map(|(c, _)| c).collect();
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl fmt::Display for Screen {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text())?;
+
+        let mut styles = Vec::new();
+        let mut keys = HashMap::new();
+        for row in self.cells.chunks(self.width.max(1)) {
+            writeln!(f)?;
+            for (_, style) in row {
+                let key = *keys.entry(style).or_insert_with(|| {
+                    styles.push(style);
+                    Self::KEYS
+                        .chars()
+                        .nth(styles.len() - 1)
+                        .expect("too many styles on screen")
+                });
+                write!(f, "{key}")?;
+            }
+        }
+
+        writeln!(f, "\n")?;
+        for (key, style) in Self::KEYS.chars().zip(styles) {
+            writeln!(f, "{key}: {style}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Check that a screen matches the named snapshot. With `UPDATE_SNAPSHOTS`
+/// set, the snapshot is written instead.
+///
+/// # Panics
+/// Panics if the snapshot is missing or doesn't match, showing where.
+pub fn assert_snapshot(name: &str, screen: &Screen) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("snapshots")
+        .join(format!("{name}.txt"));
+    let actual = screen.to_string();
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "{}: {e}\nRun with UPDATE_SNAPSHOTS=1 to create it. The screen was:\n{actual}",
+            path.display()
+        )
+    });
+    if actual != expected {
+        let (line, (expected_line, actual_line)) = expected
+            .lines()
+            .chain(std::iter::repeat(""))
+            .zip(actual.lines().chain(std::iter::repeat("")))
+            .enumerate()
+            .find(|(_, (e, a))| e != a)
+            .unwrap_or((0, ("", "")));
+        panic!(
+            "screen doesn't match {} at line {}:\n  expected: {expected_line:?}\n  \
+             actual:   {actual_line:?}\nRun with UPDATE_SNAPSHOTS=1 to update it. \
+             The screen was:\n{actual}",
+            path.display(),
+            line + 1
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plays_back_escape_codes() {
+        let mut terminal = Terminal::new((6, 2));
+        write!(
+            terminal,
+            "\x1b[?25l\x1b[2;3H\x1b[38;5;189m\x1b[48;2;1;2;3mab\x1b[0;7mcdef\
+             \x1b]52;c;aGk=\x07\x1b[1;1H\x1b[31m\x1b[1mx"
+        )
+        .unwrap();
+
+        let screen = terminal.screen();
+        assert_eq!(screen.text(), "x\n  abcd\n");
+        assert_eq!(
+            screen.to_string(),
+            "x\n  abcd\n\nabbbbb\nbbccdd\n\n\
+             a: fg 31, attributes 1;7\n\
+             b: default\n\
+             c: fg 38;5;189, bg 48;2;1;2;3\n\
+             d: attributes 7\n"
+        );
+    }
+}
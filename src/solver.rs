@@ -0,0 +1,227 @@
+//! Suggesting guesses by expected information gain.
+
+use std::cmp::Ordering;
+
+use crate::{score, Feedback};
+
+/// A candidate guess and how much it is expected to reveal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion<'a> {
+    pub word: &'a str,
+    /// Expected information gained by guessing this word, in bits.
+    pub bits: f64,
+}
+
+/// Upper bound on the number of (guess, answer) pairs scored when ranking
+/// guesses. If considering every candidate would exceed this, only the
+/// possible answers are considered, which keeps suggestions responsive.
+const SCORING_BUDGET: usize = 4_000_000;
+
+/// Filter answers down to those consistent with every guess and the feedback
+/// it got so far.
+pub fn possible_answers<'a>(
+    answers: impl IntoIterator<Item = &'a str>,
+    feedback: &[(String, Feedback)],
+) -> Vec<&'a str> {
+    answers
+        .into_iter()
+        .filter(|answer| {
+            feedback
+                .iter()
+                .all(|(guess, feedback)| score(guess, answer) == *feedback)
+        })
+        .collect()
+}
+
+/// The feedback that `guess` gets for the most possible answers,
+/// or `None` if there are none. This is the pattern an adversary would give
+/// to reveal as little as possible. Ties go to the lowest pattern, so an
+/// exact match is only given when no other answer is left.
+pub fn largest_group(guess: &str, possible: &[&str]) -> Option<Feedback> {
+    let mut counts = vec![0u32; Feedback::count(guess.len())];
+    for answer in possible {
+        counts[score(guess, answer).index()] += 1;
+    }
+
+    counts
+        .iter()
+        .enumerate()
+        .filter(|(_, &count)| count > 0)
+        .max_by(|(a, count_a), (b, count_b)| count_a.cmp(count_b).then(b.cmp(a)))
+        .map(|(index, _)| Feedback::from_index(index, guess.len()))
+}
+
+/// Counts how many possible answers produce each score pattern, reusing one
+/// buffer across many guesses.
+struct PatternCounts {
+    counts: Vec<u32>,
+    patterns: Vec<Feedback>,
+}
+
+impl PatternCounts {
+    fn new(length: usize) -> Self {
+        Self {
+            counts: vec![0; Feedback::count(length)],
+            patterns: Vec::new(),
+        }
+    }
+
+    /// Expected information from guessing `guess`: the entropy of the
+    /// distribution of patterns it produces over the possible answers.
+    fn entropy(&mut self, guess: &str, possible: &[&str]) -> f64 {
+        self.patterns.clear();
+        self.patterns
+            .extend(possible.iter().map(|answer| score(guess, answer)));
+        for &pattern in &self.patterns {
+            self.counts[pattern.index()] += 1;
+        }
+
+        let total = possible.len() as f64;
+        let mut bits = 0.0;
+        for &pattern in &self.patterns {
+            // Take each pattern's count once, resetting it for the next guess.
+            let count = std::mem::take(&mut self.counts[pattern.index()]);
+            if count > 0 {
+                let p = count as f64 / total;
+                bits -= p * p.log2();
+            }
+        }
+        bits
+    }
+}
+
+/// Expected information (in bits) from guessing `guess` when any of
+/// `possible` could be the answer.
+pub fn expected_information(guess: &str, possible: &[&str]) -> f64 {
+    PatternCounts::new(guess.len()).entropy(guess, possible)
+}
+
+/// Rank candidate guesses by expected information, best first, returning at
+/// most `count` suggestions. Ties favor guesses that could be the answer.
+///
+/// To stay quick, only the possible answers are ranked when there are too
+/// many candidates to score them all, so the suggestions may not be the
+/// best guesses overall.
+pub fn rank_guesses<'a>(
+    candidates: impl IntoIterator<Item = &'a str>,
+    possible: &[&'a str],
+    count: usize,
+) -> Vec<Suggestion<'a>> {
+    let mut candidates: Vec<_> = candidates.into_iter().collect();
+    if candidates.len() * possible.len() > SCORING_BUDGET {
+        candidates.retain(|word| possible.contains(word));
+    }
+    rank_all(candidates, possible, count)
+}
+
+/// The candidate guesses expected to reveal the most, best first, found by
+/// scoring every candidate however long that takes. Ties are broken as by
+/// `rank_guesses`.
+pub fn best_guesses<'a>(
+    candidates: impl IntoIterator<Item = &'a str>,
+    possible: &[&'a str],
+    count: usize,
+) -> Vec<Suggestion<'a>> {
+    rank_all(candidates.into_iter().collect(), possible, count)
+}
+
+/// Rank every candidate, as described by `rank_guesses`.
+fn rank_all<'a>(
+    candidates: Vec<&'a str>,
+    possible: &[&'a str],
+    count: usize,
+) -> Vec<Suggestion<'a>> {
+    let Some(length) = possible.first().map(|w| w.len()) else {
+        return Vec::new();
+    };
+
+    let mut counts = PatternCounts::new(length);
+    let mut suggestions: Vec<_> = candidates
+        .into_iter()
+        .map(|word| Suggestion {
+            word,
+            bits: counts.entropy(word, possible),
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| {
+        b.bits
+            .partial_cmp(&a.bits)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| possible.contains(&b.word).cmp(&possible.contains(&a.word)))
+            .then_with(|| a.word.cmp(b.word))
+    });
+    suggestions.truncate(count);
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_possible_answers() {
+        let answers = ["heart", "hears", "earth", "sound"];
+        let feedback = [("hoist".to_string(), score("hoist", "heart"))];
+        assert_eq!(possible_answers(answers, &feedback), vec!["heart"]);
+        assert_eq!(possible_answers(answers, &[]), answers.to_vec());
+    }
+
+    #[test]
+    fn finds_largest_groups() {
+        let possible = ["bat", "cat", "hat", "mat"];
+        assert_eq!(largest_group("bat", &possible), Some(score("bat", "cat")));
+        assert_eq!(largest_group("bcx", &possible), "...".parse().ok());
+
+        // An exact match is avoided while another answer is left.
+        assert_eq!(
+            largest_group("bat", &["bat", "cat"]),
+            Some(score("bat", "cat"))
+        );
+        assert_eq!(largest_group("bat", &["bat"]), Some(score("bat", "bat")));
+        assert_eq!(largest_group("bat", &[]), None);
+    }
+
+    #[test]
+    fn measures_information() {
+        // A guess that tells four answers apart is worth two bits.
+        let possible = ["bat", "cat", "hat", "mat"];
+        assert_eq!(expected_information("bch", &possible), 2.0);
+        assert_eq!(expected_information("bcx", &possible), 1.5);
+        assert_eq!(expected_information("bcm", &possible), 2.0);
+        assert_eq!(expected_information("xyz", &possible), 0.0);
+    }
+
+    #[test]
+    fn ranks_guesses() {
+        let possible = ["bat", "cat", "hat", "mat"];
+        let suggestions = rank_guesses(["xyz", "bat", "bcm"], &possible, 2);
+        assert_eq!(
+            suggestions,
+            vec![
+                Suggestion {
+                    word: "bcm",
+                    bits: 2.0
+                },
+                Suggestion {
+                    word: "bat",
+                    bits: 0.75 * (4.0f64 / 3.0).log2() + 0.5
+                },
+            ]
+        );
+        assert_eq!(rank_guesses(["bat"], &[], 3), vec![]);
+    }
+
+    #[test]
+    fn finds_best_guesses() {
+        let possible = ["bat", "cat", "hat", "mat"];
+        assert_eq!(
+            best_guesses(["xyz", "bat", "bcm"], &possible, 1),
+            vec![Suggestion {
+                word: "bcm",
+                bits: 2.0
+            }]
+        );
+        assert_eq!(best_guesses(["bat"], &[], 1), vec![]);
+    }
+}
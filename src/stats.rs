@@ -0,0 +1,232 @@
+//! Game history and statistics.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+
+use crate::{ColorScheme, Frame};
+
+/// The result of a single finished game.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameRecord {
+    pub date: NaiveDate,
+    pub won: bool,
+    pub guesses: usize,
+    pub answer: String,
+}
+
+/// Records are stored one per line, e.g. `2024-05-01 win 4 heart`.
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let result = if self.won { "win" } else { "loss" };
+        write!(f, "{} {result} {} {}", self.date, self.guesses, self.answer)
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<_> = s.split_whitespace().collect();
+        let [date, result, guesses, answer] = fields[..] else {
+            return Err(format!("expected 4 fields, found {}", fields.len()));
+        };
+
+        Ok(Self {
+            date: date
+                .parse()
+                .map_err(|e| format!("bad date '{date}': {e}"))?,
+            won: match result {
+                "win" => true,
+                "loss" => false,
+                other => return Err(format!("bad result '{other}'")),
+            },
+            guesses: guesses
+                .parse()
+                .map_err(|e| format!("bad guess count '{guesses}': {e}"))?,
+            answer: answer.into(),
+        })
+    }
+}
+
+/// Statistics summarizing a history of games.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Stats {
+    pub played: u32,
+    pub wins: u32,
+    pub current_streak: u32,
+    pub max_streak: u32,
+    /// Number of wins for each guess count, starting at 1.
+    pub distribution: Vec<u32>,
+}
+
+impl Stats {
+    /// Width of the drawn statistics screen.
+    const WIDTH: u16 = 39;
+
+    /// The histogram always shows at least this many guess counts.
+    const MIN_ROWS: usize = 6;
+
+    pub fn from_records(records: &[GameRecord]) -> Self {
+        let mut stats = Self {
+            played: 0,
+            wins: 0,
+            current_streak: 0,
+            max_streak: 0,
+            distribution: vec![0; Self::MIN_ROWS],
+        };
+
+        for record in records {
+            stats.played += 1;
+            if record.won {
+                stats.wins += 1;
+                stats.current_streak += 1;
+                stats.max_streak = stats.max_streak.max(stats.current_streak);
+
+                if record.guesses > stats.distribution.len() {
+                    stats.distribution.resize(record.guesses, 0);
+                }
+                if let Some(count) = record.guesses.checked_sub(1) {
+                    stats.distribution[count] += 1;
+                }
+            } else {
+                stats.current_streak = 0;
+            }
+        }
+
+        stats
+    }
+
+    /// Size (w, h) of the drawn statistics screen.
+    pub fn size(&self) -> (u16, u16) {
+        (Self::WIDTH, 9 + self.distribution.len() as u16)
+    }
+
+    /// Percentage of games won, rounded down.
+    pub fn win_percentage(&self) -> u32 {
+        (self.wins * 100).checked_div(self.played).unwrap_or(0)
+    }
+
+    /// Draw the statistics screen, with a line of instructions at the bottom.
+    /// If `highlight` is given, that guess count's bar is drawn in the
+    /// "correct" color.
+    pub fn paint(
+        &self,
+        frame: &mut Frame,
+        top_left: (u16, u16),
+        colors: &ColorScheme,
+        highlight: Option<usize>,
+        footer: &str,
+    ) {
+        let (x, y) = top_left;
+        let width = Self::WIDTH as usize;
+
+        let summary = [
+            (self.played, "Played"),
+            (self.win_percentage(), "Win %"),
+            (self.current_streak, "Streak"),
+            (self.max_streak, "Max"),
+        ];
+        let column = width / summary.len();
+        let numbers: String = summary
+            .iter()
+            .map(|(n, _)| format!("{n:^column$}"))
+            .collect();
+        let labels: String = summary
+            .iter()
+            .map(|(_, label)| format!("{label:^column$}"))
+            .collect();
+
+        frame
+            .bg(colors.game_bg)
+            .fg(colors.text_base)
+            .goto(x, y)
+            .print(format_args!("{:^width$}", "STATISTICS"))
+            .goto(x, y + 2)
+            .print(numbers)
+            .fg(colors.text_dim)
+            .goto(x, y + 3)
+            .print(labels)
+            .fg(colors.text_base)
+            .goto(x, y + 5)
+            .print(format_args!("{:^width$}", "GUESS DISTRIBUTION"));
+
+        // Scale bars so the most common guess count fills the available space.
+        let max_bar = width - 6;
+        let max_count = self.distribution.iter().copied().max().unwrap_or(0).max(1);
+
+        for (i, &count) in self.distribution.iter().enumerate() {
+            let bar_width = 1 + (count as usize * (max_bar - 1)) / max_count as usize;
+            let (text_color, bar_color) = if highlight == Some(i + 1) {
+                (colors.text_inverted, colors.cell_correct)
+            } else {
+                (colors.text_base, colors.cell_base)
+            };
+
+            frame
+                .goto(x, y + 7 + i as u16)
+                .bg(colors.game_bg)
+                .fg(colors.text_base)
+                .print(format_args!("{:>2} ", i + 1))
+                .bg(bar_color)
+                .fg(text_color)
+                .print(format_args!("{count:>bar_width$} "));
+        }
+
+        frame
+            .goto(x, y + 8 + self.distribution.len() as u16)
+            .bg(colors.game_bg)
+            .fg(colors.text_dim)
+            .print(format_args!("{footer:^width$}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(won: bool, guesses: usize) -> GameRecord {
+        GameRecord {
+            date: NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+            won,
+            guesses,
+            answer: "heart".into(),
+        }
+    }
+
+    #[test]
+    fn round_trips_records() {
+        let original = record(true, 4);
+        let line = original.to_string();
+        assert_eq!(line, "2024-05-01 win 4 heart");
+        assert_eq!(line.parse::<GameRecord>(), Ok(original));
+
+        assert!("2024-05-01 win heart".parse::<GameRecord>().is_err());
+        assert!("2024-05-01 tie 4 heart".parse::<GameRecord>().is_err());
+    }
+
+    #[test]
+    fn computes_stats() {
+        let records = [
+            record(true, 3),
+            record(true, 4),
+            record(true, 4),
+            record(false, 6),
+            record(true, 2),
+        ];
+        let stats = Stats::from_records(&records);
+        assert_eq!(
+            stats,
+            Stats {
+                played: 5,
+                wins: 4,
+                current_streak: 1,
+                max_streak: 3,
+                distribution: vec![0, 1, 1, 2, 0, 0],
+            }
+        );
+        assert_eq!(stats.win_percentage(), 80);
+        assert_eq!(Stats::from_records(&[]).win_percentage(), 0);
+    }
+}
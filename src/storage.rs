@@ -0,0 +1,220 @@
+//! Persistent data kept between sessions.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::GameRecord;
+
+/// Get the wordlers subdirectory of an XDG base directory, given the
+/// environment variable that names it and its fallback relative to `$HOME`.
+pub fn xdg_dir(var: &str, home_fallback: &str) -> Option<PathBuf> {
+    let base = std::env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(home_fallback)))?;
+
+    Some(base.join("wordlers"))
+}
+
+/// Handle to the directory where wordlers keeps its data.
+pub struct Storage {
+    dir: PathBuf,
+}
+
+impl Storage {
+    /// File holding the number of the last daily puzzle played.
+    const DAILY_FILE: &'static str = "daily";
+
+    /// File holding the history of finished games, one per line.
+    const STATS_FILE: &'static str = "stats";
+
+    /// File holding the share text of the last game saved.
+    const SHARE_FILE: &'static str = "share.txt";
+
+    /// File holding an unfinished game to resume.
+    const GAME_FILE: &'static str = "game";
+
+    /// Open the wordlers directory under the XDG data directory
+    /// (`$XDG_DATA_HOME`, falling back to `~/.local/share`). Returns `None`
+    /// if neither can be determined.
+    pub fn open() -> Option<Self> {
+        xdg_dir("XDG_DATA_HOME", ".local/share").map(Self::at)
+    }
+
+    /// Use a specific directory for storage.
+    pub fn at(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Read a data file, treating a missing file as empty.
+    fn read(&self, name: &str) -> io::Result<String> {
+        match fs::read_to_string(self.dir.join(name)) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+            result => result,
+        }
+    }
+
+    /// Write a data file, creating the storage directory if needed.
+    fn write(&self, name: &str, contents: &str) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join(name), contents)
+    }
+
+    /// Append a line to a data file, creating the storage directory if needed.
+    fn append(&self, name: &str, line: &str) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(name))?;
+        writeln!(file, "{line}")
+    }
+
+    /// Get the number of the last daily puzzle that was played, if any.
+    pub fn last_daily_played(&self) -> io::Result<Option<u32>> {
+        Ok(self.read(Self::DAILY_FILE)?.trim().parse().ok())
+    }
+
+    /// Record that a daily puzzle has been played. Finishing an older
+    /// puzzle, such as one resumed from a save, doesn't replace a newer one.
+    pub fn record_daily_played(&self, puzzle_number: u32) -> io::Result<()> {
+        if self.last_daily_played()? > Some(puzzle_number) {
+            return Ok(());
+        }
+        self.write(Self::DAILY_FILE, &format!("{puzzle_number}\n"))
+    }
+
+    /// Load the history of finished games, oldest first. Lines that can't
+    /// be read are skipped and described in `problems`, so a damaged file
+    /// only loses the games on those lines.
+    pub fn load_records(&self, problems: &mut Vec<String>) -> io::Result<Vec<GameRecord>> {
+        let path = self.dir.join(Self::STATS_FILE);
+        let mut records = Vec::new();
+        for (i, line) in self.read(Self::STATS_FILE)?.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match line.parse() {
+                Ok(record) => records.push(record),
+                Err(e) => problems.push(format!("{}:{}: {e}", path.display(), i + 1)),
+            }
+        }
+        Ok(records)
+    }
+
+    /// Add a finished game to the history.
+    pub fn record_game(&self, record: &GameRecord) -> io::Result<()> {
+        self.append(Self::STATS_FILE, &record.to_string())
+    }
+
+    /// Load the saved unfinished game, if there is one.
+    pub fn load_game(&self) -> io::Result<Option<String>> {
+        let save = self.read(Self::GAME_FILE)?;
+        Ok((!save.is_empty()).then_some(save))
+    }
+
+    /// Save an unfinished game, replacing any saved before.
+    pub fn save_game(&self, save: &str) -> io::Result<()> {
+        self.write(Self::GAME_FILE, save)
+    }
+
+    /// Remove the saved game, if there is one.
+    pub fn clear_game(&self) -> io::Result<()> {
+        match fs::remove_file(self.dir.join(Self::GAME_FILE)) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Save share text to a file, returning its path.
+    pub fn save_share_text(&self, text: &str) -> io::Result<PathBuf> {
+        self.write(Self::SHARE_FILE, &format!("{text}\n"))?;
+        Ok(self.dir.join(Self::SHARE_FILE))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// Storage in a temporary directory, which is removed when this is
+    /// dropped.
+    pub struct TempStorage(Storage);
+
+    impl std::ops::Deref for TempStorage {
+        type Target = Storage;
+
+        fn deref(&self) -> &Storage {
+            &self.0
+        }
+    }
+
+    impl Drop for TempStorage {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0.dir);
+        }
+    }
+
+    /// Get storage in a fresh temporary directory.
+    pub fn temp_storage(name: &str) -> TempStorage {
+        let dir = std::env::temp_dir().join(format!("wordlers-test-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        TempStorage(Storage::at(dir))
+    }
+
+    #[test]
+    fn records_daily_played() {
+        let storage = temp_storage("daily");
+        assert_eq!(storage.last_daily_played().unwrap(), None);
+
+        storage.record_daily_played(512).unwrap();
+        assert_eq!(storage.last_daily_played().unwrap(), Some(512));
+        storage.record_daily_played(511).unwrap();
+        assert_eq!(storage.last_daily_played().unwrap(), Some(512));
+    }
+
+    #[test]
+    fn saves_games() {
+        let storage = temp_storage("game");
+        assert_eq!(storage.load_game().unwrap(), None);
+        storage.clear_game().unwrap();
+
+        storage.save_game("wordlers save 1\n").unwrap();
+        assert_eq!(
+            storage.load_game().unwrap().as_deref(),
+            Some("wordlers save 1\n")
+        );
+
+        storage.clear_game().unwrap();
+        assert_eq!(storage.load_game().unwrap(), None);
+    }
+
+    #[test]
+    fn records_games() {
+        let storage = temp_storage("stats");
+        let mut problems = Vec::new();
+        assert_eq!(storage.load_records(&mut problems).unwrap(), vec![]);
+
+        let record = GameRecord {
+            date: chrono::NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+            won: false,
+            guesses: 6,
+            answer: "heart".into(),
+        };
+        storage.record_game(&record).unwrap();
+        storage.record_game(&record).unwrap();
+        assert_eq!(
+            storage.load_records(&mut problems).unwrap(),
+            vec![record.clone(), record.clone()]
+        );
+        assert!(problems.is_empty());
+
+        // Lines that can't be read are skipped, keeping the rest.
+        storage.append(Storage::STATS_FILE, "garbage").unwrap();
+        storage.record_game(&record).unwrap();
+        assert_eq!(storage.load_records(&mut problems).unwrap().len(), 3);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].ends_with("stats:3: expected 4 fields, found 1"));
+    }
+}
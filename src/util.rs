@@ -17,6 +17,111 @@ pub fn get_centered_top_left(outer_dim: (u16, u16), inner_dim: (u16, u16)) -> (u
     )
 }
 
+/// Format a 1-based position as an English ordinal ("1st", "2nd", ...).
+pub fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+/// Encode bytes as standard base64 with padding.
+pub fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Split a TOML-like config made of `key = value` lines into its entries,
+/// each with its line number. Blank lines and lines starting with `#` are
+/// skipped, and values may be quoted.
+pub fn config_entries(config: &str) -> impl Iterator<Item = (usize, Result<(&str, &str), String>)> {
+    config
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(n, line)| {
+            let entry = match line.split_once('=') {
+                Some((key, value)) => {
+                    let value = value.trim();
+                    let value = value
+                        .strip_prefix('"')
+                        .and_then(|v| v.strip_suffix('"'))
+                        .unwrap_or(value);
+                    Ok((key.trim(), value))
+                }
+                None => Err(format!("expected 'key = value', found '{line}'")),
+            };
+            (n, entry)
+        })
+}
+
+/// Rewrite a config made of `key = value` lines (see `config_entries`),
+/// giving each key in `settings` its new value. A key's first line is
+/// replaced and any later ones dropped, and keys that weren't in the config
+/// are added at the end. Lines for keys in `removed` are dropped first, so a
+/// key can be both removed and set to move it to the end.
+pub fn update_config(config: &str, settings: &[(&str, String)], removed: &[&str]) -> String {
+    let mut written = vec![false; settings.len()];
+    let mut updated = String::new();
+
+    for line in config.lines() {
+        let trimmed = line.trim();
+        let key = trimmed
+            .split_once('=')
+            .filter(|_| !trimmed.starts_with('#'))
+            .map(|(key, _)| key.trim());
+
+        if let Some(key) = key {
+            if removed.contains(&key) {
+                continue;
+            }
+            if let Some(i) = settings.iter().position(|&(k, _)| k == key) {
+                if !written[i] {
+                    updated += &format!("{key} = {}\n", settings[i].1);
+                    written[i] = true;
+                }
+                continue;
+            }
+        }
+        updated += line;
+        updated.push('\n');
+    }
+
+    for ((key, value), _) in settings.iter().zip(written).filter(|(_, w)| !w) {
+        updated += &format!("{key} = {value}\n");
+    }
+    updated
+}
+
+/// Parse a config value that must be `true` or `false`.
+pub fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("expected 'true' or 'false', not '{value}'")),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -26,4 +131,26 @@ mod tests {
         assert_eq!(get_centered_top_left((10, 10), (8, 8)), (2, 2));
         assert_eq!(get_centered_top_left((5, 5), (8, 8)), (1, 1));
     }
+
+    #[test]
+    fn formats_ordinals() {
+        assert_eq!(ordinal(1), "1st");
+        assert_eq!(ordinal(2), "2nd");
+        assert_eq!(ordinal(3), "3rd");
+        assert_eq!(ordinal(4), "4th");
+        assert_eq!(ordinal(11), "11th");
+        assert_eq!(ordinal(22), "22nd");
+    }
+
+    #[test]
+    fn encodes_base64() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(
+            base64_encode("wordlers 🟩".as_bytes()),
+            "d29yZGxlcnMg8J+fqQ=="
+        );
+    }
 }
@@ -0,0 +1,573 @@
+//! Drawing a game, and animating changes to it.
+
+use wordlers::{Board, BoardRow, Cell, Game, Keyboard, Mark};
+
+use crate::{ColorScheme, Frame};
+
+/// Draws a game with a message beneath it, and plays the animations that
+/// follow a guess.
+#[derive(Default)]
+pub struct GameView {
+    animation: Option<Animation>,
+    message: Option<String>,
+}
+
+/// How the boards are arranged on screen.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Layout {
+    /// Number of boards side by side.
+    columns: usize,
+    style: CellStyle,
+}
+
+/// How big cells are drawn.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum CellStyle {
+    /// A letter in a block three lines high.
+    Full,
+    /// A letter on a single line, for fitting more boards on screen.
+    Compact,
+}
+
+impl CellStyle {
+    /// Size (w, h) of a single cell.
+    fn size(self) -> (u16, u16) {
+        match self {
+            Self::Full => (5, 3),
+            Self::Compact => (3, 1),
+        }
+    }
+}
+
+impl GameView {
+    /// Space (w, h) left between neighboring boards.
+    const BOARD_GAP: (u16, u16) = (2, 1);
+
+    /// Keys in the order they are drawn.
+    const KEYBOARD_LAYOUT: [&'static str; 3] = ["QWERTYUIOP", "ASDFGHJKL", "ZXCVBNM"];
+
+    /// Size (w, h) of the drawn keyboard, including a blank row above it.
+    const KEYBOARD_SIZE: (u16, u16) = (Self::KEYBOARD_LAYOUT[0].len() as u16 * 4 - 1, 4);
+
+    /// Show a message beneath the keyboard, until it's cleared or replaced.
+    pub fn set_message(&mut self, message: &str) {
+        self.message = Some(message.into());
+    }
+
+    pub fn clear_message(&mut self) {
+        self.message = None;
+    }
+
+    /// Flip over the cells of a row that was just checked, showing
+    /// `keyboard`, as it was before the guess, until they have all turned.
+    pub fn reveal(&mut self, row: usize, keyboard: Keyboard) {
+        self.animation = Some(Animation::Reveal {
+            row,
+            frame: 0,
+            keyboard,
+        });
+    }
+
+    /// Shake the current row after a rejected guess.
+    pub fn shake(&mut self) {
+        self.animation = Some(Animation::Shake { frame: 0 });
+    }
+
+    /// Whether an animation is playing.
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    /// Advance the animation by a frame, ending it after the last one.
+    pub fn tick(&mut self, game: &Game) {
+        if let Some(animation) = &mut self.animation {
+            if !animation.advance(game.length()) {
+                self.animation = None;
+            }
+        }
+    }
+
+    /// Jump to the end of the animation, if one is playing.
+    pub fn skip_animation(&mut self) {
+        self.animation = None;
+    }
+
+    /// The size (w, h) of the wordle boards drawn with characters, arranged
+    /// to fit in `available` space if possible. Includes the keyboard and
+    /// two extra rows at the bottom for a message.
+    pub fn size(&self, game: &Game, available: (u16, u16)) -> (u16, u16) {
+        size_with(game, layout(game, available))
+    }
+
+    /// Draw the game, arranging the boards to fit in `available` space.
+    pub fn paint(
+        &self,
+        game: &Game,
+        frame: &mut Frame,
+        top_left: (u16, u16),
+        colors: &ColorScheme,
+        available: (u16, u16),
+    ) {
+        let (x, y) = top_left;
+        let layout = layout(game, available);
+        let (width, height) = size_with(game, layout);
+        let (grids_width, grids_height) = grids_size(game, layout);
+        let (cell_width, cell_height) = layout.style.size();
+        let grid_x = x + (width - grids_width) / 2;
+
+        for (i, board) in game.boards().iter().enumerate() {
+            let column = (i % layout.columns) as u16;
+            let board_row = (i / layout.columns) as u16;
+            let board_x = column * (cell_width * game.length() as u16 + Self::BOARD_GAP.0);
+            let board_y = board_row * (cell_height * game.rows() as u16 + Self::BOARD_GAP.1);
+            paint_board(
+                board,
+                frame,
+                (grid_x + board_x, y + board_y),
+                colors,
+                layout.style,
+                game.current_row(),
+                self.animation.as_ref(),
+            );
+        }
+
+        // Center the keyboard and message beneath the boards. The keyboard
+        // isn't updated until a row has been revealed.
+        let keyboard_x = x + (width - Self::KEYBOARD_SIZE.0) / 2;
+        let keyboard_y = y + grids_height;
+        let keyboard = match &self.animation {
+            Some(Animation::Reveal { keyboard, .. }) => keyboard,
+            _ => game.keyboard(),
+        };
+        paint_keyboard(keyboard, frame, (keyboard_x, keyboard_y), colors);
+
+        if let Some(message) = &self.message {
+            // Write up to two wrapped message lines beneath the board.
+            let lines = textwrap::wrap(message, Self::KEYBOARD_SIZE.0 as usize);
+            frame.bg(colors.game_bg).fg(colors.text_base);
+            for (i, line) in lines.iter().take(2).enumerate() {
+                let y_offset = height - 2 + (i as u16);
+                frame.goto(keyboard_x, y + y_offset).print(line);
+            }
+        }
+    }
+}
+
+/// Choose how to arrange a game's boards in the available space. Full-size
+/// cells are preferred, then as few rows of boards as possible. If no
+/// arrangement fits, the smallest one is used.
+fn layout(game: &Game, available: (u16, u16)) -> Layout {
+    let n = game.board_count();
+    let layouts: Vec<_> = [CellStyle::Full, CellStyle::Compact]
+        .into_iter()
+        .flat_map(|style| {
+            (1..=n).map(move |board_rows| Layout {
+                columns: n.div_ceil(board_rows),
+                style,
+            })
+        })
+        .collect();
+
+    let area = |layout: &Layout| {
+        let (w, h) = size_with(game, *layout);
+        w as u32 * h as u32
+    };
+    layouts
+        .iter()
+        .copied()
+        .find(|&layout| {
+            let (w, h) = size_with(game, layout);
+            w <= available.0 && h <= available.1
+        })
+        .or_else(|| layouts.iter().copied().min_by_key(area))
+        .expect("There is at least one board")
+}
+
+/// The size (w, h) of everything drawn, for a given layout.
+fn size_with(game: &Game, layout: Layout) -> (u16, u16) {
+    let (grids_width, grids_height) = grids_size(game, layout);
+    (
+        grids_width.max(GameView::KEYBOARD_SIZE.0),
+        grids_height + GameView::KEYBOARD_SIZE.1 + 2,
+    )
+}
+
+/// The size (w, h) of all the boards' grids of cells, for a given layout.
+fn grids_size(game: &Game, layout: Layout) -> (u16, u16) {
+    let (cell_width, cell_height) = layout.style.size();
+    let (gap_width, gap_height) = GameView::BOARD_GAP;
+    let columns = layout.columns as u16;
+    let board_rows = game.board_count().div_ceil(layout.columns) as u16;
+    (
+        columns * cell_width * game.length() as u16 + (columns - 1) * gap_width,
+        board_rows * cell_height * game.rows() as u16 + (board_rows - 1) * gap_height,
+    )
+}
+
+/// Draw a board's rows. Only an unsolved board shows the current row as
+/// active, and only its rows are animated.
+fn paint_board(
+    board: &Board,
+    frame: &mut Frame,
+    top_left: (u16, u16),
+    colors: &ColorScheme,
+    style: CellStyle,
+    current_row: usize,
+    animation: Option<&Animation>,
+) {
+    let (x, y) = top_left;
+
+    for (i, row) in board.rows().iter().enumerate() {
+        let active = !board.is_solved() && i == current_row;
+        let (x_offset, reveal_frame) = match animation {
+            Some(&Animation::Shake { frame }) if active => (Animation::SHAKE[frame], None),
+            // A board solved earlier has nothing to reveal.
+            Some(&Animation::Reveal {
+                row: revealed,
+                frame,
+                ..
+            }) if i == revealed && row.feedback().is_some() => (0, Some(frame)),
+            _ => (0, None),
+        };
+
+        let y_offset = (i as u16) * style.size().1;
+        paint_row(
+            row,
+            frame,
+            (x.saturating_add_signed(x_offset), y + y_offset),
+            colors,
+            style,
+            active,
+            reveal_frame,
+        );
+    }
+}
+
+/// Draw a row. While it's being revealed, `reveal_frame` is the frame of
+/// the animation: each cell in turn is flipped over, showing its edge for
+/// two frames, before its hint is shown.
+fn paint_row(
+    row: &BoardRow,
+    frame: &mut Frame,
+    top_left: (u16, u16),
+    colors: &ColorScheme,
+    style: CellStyle,
+    active: bool,
+    reveal_frame: Option<usize>,
+) {
+    let (x, y) = top_left;
+
+    for (i, &cell) in row.cells().iter().enumerate() {
+        let cell_top_left = (x + (i as u16) * style.size().0, y);
+        let pending = Cell::Pending(Some(cell.get_char()));
+        let flip_frame = reveal_frame.map(|n| n as isize - (i * 2) as isize);
+        match flip_frame {
+            Some(..0) => paint_cell(pending, frame, cell_top_left, colors, style, active, false),
+            Some(0) => paint_cell_edge(pending, frame, cell_top_left, colors, style),
+            Some(1) => paint_cell_edge(cell, frame, cell_top_left, colors, style),
+            _ => paint_cell(
+                cell,
+                frame,
+                cell_top_left,
+                colors,
+                style,
+                active,
+                row.cursor() == Some(i),
+            ),
+        }
+    }
+}
+
+/// Draw a single compact cell for `letter`, colored by `mark`, as an example
+/// of what the colors mean.
+pub fn paint_example_cell(
+    frame: &mut Frame,
+    top_left: (u16, u16),
+    colors: &ColorScheme,
+    letter: char,
+    mark: Mark,
+) {
+    let cell = match mark {
+        Mark::Correct => Cell::Correct(letter),
+        Mark::Present => Cell::InWord(letter),
+        Mark::Absent => Cell::NotInWord(letter),
+    };
+    paint_cell(
+        cell,
+        frame,
+        top_left,
+        colors,
+        CellStyle::Compact,
+        false,
+        false,
+    );
+}
+
+fn paint_cell(
+    cell: Cell,
+    frame: &mut Frame,
+    top_left: (u16, u16),
+    colors: &ColorScheme,
+    style: CellStyle,
+    row_active: bool,
+    cell_active: bool,
+) {
+    let (x, y) = top_left;
+    let bg_color = colors.game_bg;
+    let cell_char = cell.get_char();
+
+    let base_color = if row_active {
+        if cell_active {
+            colors.cell_active
+        } else {
+            colors.cell_row_active
+        }
+    } else {
+        colors.cell_base
+    };
+
+    let (text_color, cell_color) = match cell {
+        Cell::Pending(_) | Cell::NotInWord(_) => (colors.text_base, base_color),
+        Cell::InWord(_) => (colors.text_inverted, colors.cell_in_word),
+        Cell::Correct(_) => (colors.text_inverted, colors.cell_correct),
+    };
+
+    // With cues, hints are also marked in the border: the sides of a
+    // compact cell, or the middle of a full cell's bottom edge.
+    let cue = cue(cell).filter(|_| colors.cues);
+
+    if style == CellStyle::Compact {
+        let (left, right) = cue.map_or(('▐', '▌'), |[left, _, right]| (left, right));
+        frame
+            .goto(x, y)
+            .bg(bg_color)
+            .fg(cell_color)
+            .print(left)
+            .bg(cell_color)
+            .fg(text_color)
+            .print(cell_char)
+            .bg(bg_color)
+            .fg(cell_color)
+            .print(right);
+        return;
+    }
+
+    let mark = cue.map_or('▀', |[_, mark, _]| mark);
+    frame
+        .goto(x, y) // Row 1.
+        .bg(bg_color)
+        .fg(cell_color)
+        .print(" ▄▄▄ ")
+        .goto(x, y + 1) // Row 2.
+        .print(" █")
+        .bg(cell_color)
+        .fg(text_color)
+        .print(cell_char)
+        .bg(bg_color)
+        .fg(cell_color)
+        .print("█ ")
+        .goto(x, y + 2) // Row 3.
+        .print(format_args!(" ▀{mark}▀ "));
+}
+
+/// Draw a cell seen edge-on, halfway through being flipped over.
+fn paint_cell_edge(
+    cell: Cell,
+    frame: &mut Frame,
+    top_left: (u16, u16),
+    colors: &ColorScheme,
+    style: CellStyle,
+) {
+    let (x, y) = top_left;
+    let cell_color = match cell {
+        Cell::Pending(_) | Cell::NotInWord(_) => colors.cell_base,
+        Cell::InWord(_) => colors.cell_in_word,
+        Cell::Correct(_) => colors.cell_correct,
+    };
+
+    frame.goto(x, y).bg(colors.game_bg).fg(cell_color);
+    if style == CellStyle::Compact {
+        frame.print(" █ ");
+        return;
+    }
+
+    frame
+        .print("     ")
+        .goto(x, y + 1)
+        .print(" ███ ")
+        .goto(x, y + 2)
+        .print("     ");
+}
+
+/// Shapes that mark a hint without relying on color, as an opening bracket,
+/// a mark and a closing bracket: round for a letter in the word and square
+/// for a correct one.
+fn cue(cell: Cell) -> Option<[char; 3]> {
+    match cell {
+        Cell::Pending(_) | Cell::NotInWord(_) => None,
+        Cell::InWord(_) => Some(['(', '○', ')']),
+        Cell::Correct(_) => Some(['[', '●', ']']),
+    }
+}
+
+fn paint_keyboard(
+    keyboard: &Keyboard,
+    frame: &mut Frame,
+    top_left: (u16, u16),
+    colors: &ColorScheme,
+) {
+    let (x, y) = top_left;
+
+    for (i, keys) in GameView::KEYBOARD_LAYOUT.iter().enumerate() {
+        let row_width = keys.len() as u16 * 4 - 1;
+        let x_offset = (GameView::KEYBOARD_SIZE.0 - row_width) / 2;
+        frame.goto(x + x_offset, y + 1 + i as u16);
+
+        for (j, key) in keys.chars().enumerate() {
+            let cell = keyboard.get(key);
+            let (text_color, key_color) = match cell {
+                None | Some(Cell::Pending(_)) => (colors.text_base, colors.cell_base),
+                Some(Cell::NotInWord(_)) => (colors.text_dim, colors.game_bg),
+                Some(Cell::InWord(_)) => (colors.text_inverted, colors.cell_in_word),
+                Some(Cell::Correct(_)) => (colors.text_inverted, colors.cell_correct),
+            };
+            if j > 0 {
+                frame.bg(colors.game_bg).print(' ');
+            }
+            let cue = cell.and_then(cue).filter(|_| colors.cues);
+            let (left, right) = cue.map_or((' ', ' '), |[left, _, right]| (left, right));
+            frame
+                .bg(key_color)
+                .fg(text_color)
+                .print(format_args!("{left}{key}{right}"));
+        }
+    }
+}
+
+/// A short animation of the board, advanced a frame at a time by
+/// `GameView::tick`.
+enum Animation {
+    /// Flip the cells of a newly checked row over one by one to reveal their
+    /// hints. The keyboard is shown as it was before the guess until the end.
+    Reveal {
+        row: usize,
+        frame: usize,
+        keyboard: Keyboard,
+    },
+    /// Shake the current row from side to side after a rejected guess.
+    Shake { frame: usize },
+}
+
+impl Animation {
+    /// Horizontal offset of the row in each frame of a shake.
+    const SHAKE: [i16; 6] = [-1, 1, -1, 1, -1, 0];
+
+    /// Move to the next frame of the animation, for words of the given
+    /// length. Returns false if the animation is over.
+    fn advance(&mut self, length: usize) -> bool {
+        let (frame, frames) = match self {
+            // Each cell starts flipping two frames after the previous one,
+            // and is revealed two frames after it starts.
+            Self::Reveal { frame, .. } => (frame, length * 2),
+            Self::Shake { frame } => (frame, Self::SHAKE.len()),
+        };
+        *frame += 1;
+        *frame < frames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PlainRenderer, Renderer};
+    use std::rc::Rc;
+    use wordlers::{Settings, Words};
+
+    fn get_game(length: usize, boards: usize) -> Game {
+        let settings = Settings {
+            rows: Settings::default_rows(length, boards),
+            boards,
+            ..Default::default()
+        };
+        Game::new(&settings, Rc::new(Words::new(length)))
+    }
+
+    #[test]
+    fn lays_out_boards() {
+        let view = GameView::default();
+        let game = get_game(5, 4);
+        let layout = |columns, style| Layout { columns, style };
+
+        // Full-size cells in as few rows as will fit, then compact cells.
+        assert_eq!(super::layout(&game, (200, 60)), layout(4, CellStyle::Full));
+        assert_eq!(view.size(&game, (200, 60)), (4 * 25 + 3 * 2, 27 + 6));
+        assert_eq!(super::layout(&game, (80, 70)), layout(2, CellStyle::Full));
+        assert_eq!(
+            super::layout(&game, (80, 40)),
+            layout(4, CellStyle::Compact)
+        );
+        assert_eq!(view.size(&game, (80, 40)), (4 * 15 + 3 * 2, 9 + 6));
+        assert_eq!(
+            super::layout(&game, (40, 40)),
+            layout(2, CellStyle::Compact)
+        );
+
+        // If nothing fits, the smallest layout is used.
+        assert_eq!(super::layout(&game, (10, 10)).style, CellStyle::Compact);
+
+        // Long words get more rows.
+        let game = get_game(9, 1);
+        let (cell_width, cell_height) = CellStyle::Full.size();
+        assert_eq!(
+            view.size(&game, (200, 100)),
+            (cell_width * 9, cell_height * 10 + 6)
+        );
+    }
+
+    #[test]
+    fn animates_guesses() {
+        let frames = |view: &mut GameView, game: &Game| {
+            let mut frames = 0;
+            while view.is_animating() {
+                view.tick(game);
+                frames += 1;
+            }
+            frames
+        };
+
+        let game = get_game(5, 1);
+        let mut view = GameView::default();
+        view.reveal(0, game.keyboard().clone());
+        assert_eq!(frames(&mut view, &game), 10);
+
+        view.shake();
+        assert_eq!(frames(&mut view, &game), Animation::SHAKE.len());
+
+        view.shake();
+        view.skip_animation();
+        assert!(!view.is_animating());
+    }
+
+    #[test]
+    fn paints_cues() {
+        // The last line drawn for a cell, with trailing spaces removed.
+        let paint = |cell: Cell, cues: bool, style: CellStyle| {
+            let mut colors = crate::Theme::Mocha.colors(None);
+            colors.cues = cues;
+            let mut frame = Frame::new(style.size(), colors.text_base, colors.game_bg);
+            paint_cell(cell, &mut frame, (1, 1), &colors, style, false, false);
+            let mut text = Vec::new();
+            PlainRenderer.render(&frame, &mut text).unwrap();
+            let text = String::from_utf8(text).unwrap();
+            text.lines().last().unwrap().to_string()
+        };
+
+        assert_eq!(paint(Cell::Correct('A'), true, CellStyle::Full), " ▀●▀");
+        assert_eq!(paint(Cell::InWord('A'), true, CellStyle::Full), " ▀○▀");
+        assert_eq!(paint(Cell::InWord('A'), false, CellStyle::Full), " ▀▀▀");
+        assert_eq!(paint(Cell::NotInWord('A'), true, CellStyle::Full), " ▀▀▀");
+        assert_eq!(paint(Cell::Correct('A'), true, CellStyle::Compact), "[A]");
+        assert_eq!(paint(Cell::InWord('A'), true, CellStyle::Compact), "(A)");
+        assert_eq!(paint(Cell::InWord('A'), false, CellStyle::Compact), "▐A▌");
+    }
+}
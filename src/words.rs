@@ -1,44 +1,368 @@
+use chrono::NaiveDate;
 use rand::seq::SliceRandom;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use std::thread;
+
+use crate::solver;
+
+/// The date of daily puzzle #0.
+const DAILY_EPOCH: NaiveDate = match NaiveDate::from_ymd_opt(2021, 6, 19) {
+    Some(date) => date,
+    None => panic!("invalid epoch"),
+};
+
+/// Seed for the fixed shuffle that orders the daily answers. Changing this
+/// changes every daily puzzle, so don't.
+const DAILY_SEED: u64 = 0x776f_7264_6c65_7273;
 
 /// Struct for holding dictionary data, choosing an answer,
-/// and validating user guesses.
+/// and validating user guesses. Clones share the opening guesses once
+/// they're worked out.
+#[derive(Clone)]
 pub struct Words {
-    answers: Vec<&'static str>,
-    valid_guesses: HashSet<&'static str>,
+    answers: Vec<String>,
+    valid_guesses: HashSet<String>,
+    /// The best opening guesses, once worked out (see `opening_guesses`).
+    opening: Arc<OnceLock<Vec<(String, f64)>>>,
+}
+
+/// A word list along with where it came from, for reporting problems.
+struct WordList {
+    source: String,
+    /// Each word with the line number it was found on.
+    words: Vec<(usize, String)>,
+}
+
+impl WordList {
+    /// Read a list with one word per line, skipping blank lines and `#`
+    /// comments. Entries that aren't usable are left out and described in
+    /// `problems`.
+    fn parse(source: String, contents: &str, length: usize, problems: &mut Vec<String>) -> Self {
+        let mut words = Vec::new();
+        let mut first_seen = HashMap::new();
+
+        for (i, line) in contents.lines().enumerate() {
+            let line_number = i + 1;
+            let entry = line.trim();
+            if entry.is_empty() || entry.starts_with('#') {
+                continue;
+            }
+
+            let location = format!("{source}:{line_number}");
+            let word = entry.to_ascii_lowercase();
+            if !word.chars().all(|c| c.is_ascii_alphabetic()) {
+                problems.push(format!(
+                    "{location}: '{entry}' contains non-alphabetic characters"
+                ));
+            } else if word.len() != length {
+                problems.push(format!(
+                    "{location}: '{entry}' has {} letters, expected {length}",
+                    word.len()
+                ));
+            } else if let Some(first) = first_seen.get(&word) {
+                problems.push(format!(
+                    "{location}: '{entry}' is a duplicate of line {first}"
+                ));
+            } else {
+                first_seen.insert(word.clone(), line_number);
+                words.push((line_number, word));
+            }
+        }
+
+        Self { source, words }
+    }
+
+    /// Read a list from a file.
+    fn read(path: &Path, length: usize, problems: &mut Vec<String>) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+        Ok(Self::parse(
+            path.display().to_string(),
+            &contents,
+            length,
+            problems,
+        ))
+    }
 }
 
 impl Words {
-    pub fn new() -> Self {
-        // Wordle dictionaries sourced from
-        // https://gist.github.com/scholtes/94f3c0303ba6a7768b47583aff36654d
-        let la: Vec<_> = include_str!("../words/wordle-La.txt").lines().collect();
-        let ta: Vec<_> = include_str!("../words/wordle-Ta.txt").lines().collect();
+    /// Shortest supported word length.
+    pub const MIN_LENGTH: usize = 4;
+
+    /// Longest supported word length.
+    pub const MAX_LENGTH: usize = 11;
+
+    /// Most problems listed when word lists fail to load.
+    const MAX_PROBLEMS: usize = 20;
+
+    /// Number of opening guesses kept by `opening_guesses`.
+    pub const OPENING_GUESSES: usize = 3;
+
+    /// Load the built-in dictionaries for words of the given length.
+    ///
+    /// # Panics
+    /// This method panics if `length` is not between `MIN_LENGTH` and
+    /// `MAX_LENGTH`.
+    pub fn new(length: usize) -> Self {
+        let (la, ta) = Self::builtin_lists(length);
+        let la: Vec<_> = la.lines().map(String::from).collect();
+        let ta: Vec<_> = ta.lines().map(String::from).collect();
 
         let answers = la.clone();
-        let valid_guesses = la.into_iter().chain(ta.into_iter()).collect();
+        let valid_guesses = la.into_iter().chain(ta).collect();
 
         Self {
             answers,
             valid_guesses,
+            opening: Arc::default(),
         }
     }
 
-    /// Choose an answer from the possible answer dictionary.
+    /// Load dictionaries for words of the given length, replacing the
+    /// built-in answer or guess lists with files where given.
     ///
-    /// # Panics
-    /// This method panics if the answers failed to load.
-    pub fn get_answer(&self) -> &'static str {
-        *self
+    /// Every answer must also be a valid guess. If only an answer file is
+    /// given, its words are allowed as guesses along with the built-in ones.
+    /// All problems found in the lists are reported together, with file and
+    /// line numbers.
+    pub fn load(
+        length: usize,
+        answers_path: Option<&Path>,
+        guesses_path: Option<&Path>,
+    ) -> io::Result<Self> {
+        let mut problems = Vec::new();
+        let answers = answers_path
+            .map(|path| WordList::read(path, length, &mut problems))
+            .transpose()?;
+        let guesses = guesses_path
+            .map(|path| WordList::read(path, length, &mut problems))
+            .transpose()?;
+        Self::from_lists(length, answers, guesses, problems)
+    }
+
+    /// Make dictionaries from the contents of answer and guess lists, as
+    /// `load` does with files, replacing the built-in lists where given.
+    /// Problems are reported with `answers` or `guesses` and line numbers.
+    pub fn parse(length: usize, answers: Option<&str>, guesses: Option<&str>) -> io::Result<Self> {
+        let mut problems = Vec::new();
+        let mut parse = |source: &str, contents| {
+            WordList::parse(source.into(), contents, length, &mut problems)
+        };
+        let answers = answers.map(|contents| parse("answers", contents));
+        let guesses = guesses.map(|contents| parse("guesses", contents));
+        Self::from_lists(length, answers, guesses, problems)
+    }
+
+    /// Make dictionaries from word lists replacing the built-in ones, given
+    /// the problems found reading them so far.
+    fn from_lists(
+        length: usize,
+        answers: Option<WordList>,
+        guesses: Option<WordList>,
+        mut problems: Vec<String>,
+    ) -> io::Result<Self> {
+        if answers.is_none() && guesses.is_none() {
+            return Ok(Self::new(length));
+        }
+
+        let answers = answers.unwrap_or_else(|| {
+            WordList::parse(
+                "built-in answers".into(),
+                Self::builtin_lists(length).0,
+                length,
+                &mut problems,
+            )
+        });
+        if answers.words.is_empty() {
+            problems.push(format!("{}: no usable answers", answers.source));
+        }
+
+        let valid_guesses: HashSet<_> = match guesses {
+            Some(guesses) => {
+                let source = guesses.source;
+                let valid_guesses: HashSet<_> = guesses.words.into_iter().map(|(_, w)| w).collect();
+                for (line_number, word) in &answers.words {
+                    if !valid_guesses.contains(word) {
+                        problems.push(format!(
+                            "{}:{line_number}: answer '{word}' is missing from {source}",
+                            answers.source,
+                        ));
+                    }
+                }
+                valid_guesses
+            }
+            None => Self::new(length)
+                .valid_guesses
+                .into_iter()
+                .chain(answers.words.iter().map(|(_, w)| w.clone()))
+                .collect(),
+        };
+
+        if !problems.is_empty() {
+            let count = problems.len();
+            let mut message = String::from("problems in word lists:");
+            for problem in problems.into_iter().take(Self::MAX_PROBLEMS) {
+                message.push_str("\n  ");
+                message.push_str(&problem);
+            }
+            if count > Self::MAX_PROBLEMS {
+                message.push_str(&format!("\n  ...and {} more", count - Self::MAX_PROBLEMS));
+            }
+            return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+        }
+
+        Ok(Self {
+            answers: answers.words.into_iter().map(|(_, w)| w).collect(),
+            valid_guesses,
+            opening: Arc::default(),
+        })
+    }
+
+    /// Get the built-in answer ("La") and other allowed guess ("Ta") lists.
+    fn builtin_lists(length: usize) -> (&'static str, &'static str) {
+        // Wordle dictionaries sourced from
+        // https://gist.github.com/scholtes/94f3c0303ba6a7768b47583aff36654d
+        //
+        // Other lengths are built by scripts/build-word-lists.py from the
+        // word lists in the petname, names, bip39 and stop-words crates (see
+        // the README): "La" holds answers, "Ta" holds other allowed guesses.
+        match length {
+            4 => (
+                include_str!("../words/wordle-La-4.txt"),
+                include_str!("../words/wordle-Ta-4.txt"),
+            ),
+            5 => (
+                include_str!("../words/wordle-La.txt"),
+                include_str!("../words/wordle-Ta.txt"),
+            ),
+            6 => (
+                include_str!("../words/wordle-La-6.txt"),
+                include_str!("../words/wordle-Ta-6.txt"),
+            ),
+            7 => (
+                include_str!("../words/wordle-La-7.txt"),
+                include_str!("../words/wordle-Ta-7.txt"),
+            ),
+            8 => (
+                include_str!("../words/wordle-La-8.txt"),
+                include_str!("../words/wordle-Ta-8.txt"),
+            ),
+            9 => (
+                include_str!("../words/wordle-La-9.txt"),
+                include_str!("../words/wordle-Ta-9.txt"),
+            ),
+            10 => (
+                include_str!("../words/wordle-La-10.txt"),
+                include_str!("../words/wordle-Ta-10.txt"),
+            ),
+            11 => (
+                include_str!("../words/wordle-La-11.txt"),
+                include_str!("../words/wordle-Ta-11.txt"),
+            ),
+            _ => panic!("Unsupported word length {length}!"),
+        }
+    }
+
+    /// Choose answers from the possible answer dictionary, all different
+    /// unless there are too few to choose from.
+    pub fn get_answers(&self, count: usize) -> Vec<&str> {
+        let mut rng = rand::thread_rng();
+        let mut answers: Vec<_> = self
             .answers
-            .choose(&mut rand::thread_rng())
-            .expect("Failed to load answers!")
+            .choose_multiple(&mut rng, count)
+            .map(String::as_str)
+            .collect();
+        while answers.len() < count {
+            let answer = self.answers.choose(&mut rng);
+            answers.push(answer.expect("Answers are checked when loaded"));
+        }
+        answers
+    }
+
+    /// Get the daily puzzle number for a date, counted in days since a fixed
+    /// epoch. Returns `None` for dates before the epoch.
+    pub fn puzzle_number(date: NaiveDate) -> Option<u32> {
+        u32::try_from((date - DAILY_EPOCH).num_days()).ok()
+    }
+
+    /// Get the daily puzzle number for today's local date.
+    pub fn todays_puzzle_number() -> u32 {
+        Self::puzzle_number(chrono::Local::now().date_naive()).unwrap_or_default()
+    }
+
+    /// Choose the answer for a daily puzzle. Everyone with the same word
+    /// lists gets the same answer for the same puzzle number, and no answer
+    /// repeats until the whole list has been used.
+    pub fn get_daily_answer(&self, puzzle_number: u32) -> &str {
+        // Shuffle the answers with a small, portable PRNG (splitmix64) so the
+        // order doesn't depend on `rand`'s implementation details.
+        let mut state = DAILY_SEED;
+        let mut next = || {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+
+        let mut order: Vec<_> = self.answers.iter().collect();
+        for i in (1..order.len()).rev() {
+            let j = (next() % (i as u64 + 1)) as usize;
+            order.swap(i, j);
+        }
+
+        order[puzzle_number as usize % order.len()]
+    }
+
+    /// Number of letters in each word.
+    pub fn length(&self) -> usize {
+        self.answers[0].len()
+    }
+
+    /// All possible answers.
+    pub fn answers(&self) -> &[String] {
+        &self.answers
+    }
+
+    /// All valid guesses, in no particular order.
+    pub fn guesses(&self) -> impl Iterator<Item = &str> {
+        self.valid_guesses.iter().map(String::as_str)
     }
 
     /// Check if a word is a valid guess.
     pub fn valid_guess(&self, word: &str) -> bool {
         self.valid_guesses.contains(word)
     }
+
+    /// The guesses expected to reveal the most before anything is known,
+    /// best first, with their expected information in bits. Every guess is
+    /// scored against every answer, which takes a while for the full lists,
+    /// so it's only done once and can be started early with
+    /// `prepare_opening_guesses`.
+    pub fn opening_guesses(&self) -> &[(String, f64)] {
+        self.opening.get_or_init(|| {
+            let answers: Vec<_> = self.answers.iter().map(String::as_str).collect();
+            solver::best_guesses(self.guesses(), &answers, Self::OPENING_GUESSES)
+                .into_iter()
+                .map(|s| (s.word.to_string(), s.bits))
+                .collect()
+        })
+    }
+
+    /// Start working out the opening guesses on another thread, so they're
+    /// ready by the time they're needed.
+    pub fn prepare_opening_guesses(&self) {
+        if self.opening.get().is_none() {
+            let words = self.clone();
+            thread::spawn(move || {
+                words.opening_guesses();
+            });
+        }
+    }
 }
 
 #[cfg(test)]
@@ -47,14 +371,115 @@ mod tests {
 
     #[test]
     fn answers_load() {
-        let words = Words::new();
-        let answer = words.get_answer();
-        assert_eq!(answer.len(), 5);
+        for length in Words::MIN_LENGTH..=Words::MAX_LENGTH {
+            let words = Words::new(length);
+            let answer = words.get_answers(1)[0];
+            assert_eq!(answer.len(), length);
+            assert!(words.valid_guess(answer));
+            assert!(words.valid_guesses.iter().all(|w| w.len() == length));
+        }
+    }
+
+    #[test]
+    fn daily_answers_are_deterministic() {
+        let words = Words::new(5);
+        assert_eq!(words.get_daily_answer(512), words.get_daily_answer(512));
+        assert_ne!(words.get_daily_answer(512), words.get_daily_answer(513));
+
+        let first_cycle: HashSet<_> = (0..words.answers.len() as u32)
+            .map(|n| words.get_daily_answer(n))
+            .collect();
+        assert_eq!(first_cycle.len(), words.answers.len());
+    }
+
+    #[test]
+    fn numbers_puzzles_from_epoch() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(Words::puzzle_number(date(2021, 6, 19)), Some(0));
+        assert_eq!(Words::puzzle_number(date(2022, 6, 19)), Some(365));
+        assert_eq!(Words::puzzle_number(date(2021, 6, 18)), None);
+    }
+
+    #[test]
+    fn finds_opening_guesses() {
+        let words = Words::parse(
+            5,
+            Some("crate\ntrace\nheart\nsound\n"),
+            Some("crate\ntrace\nheart\nsound\nearth\n"),
+        )
+        .unwrap();
+
+        // Worked out on another thread, and shared with clones.
+        words.prepare_opening_guesses();
+        let opening = words.clone().opening_guesses().to_vec();
+        assert_eq!(words.opening_guesses(), opening);
+        assert_eq!(opening.len(), Words::OPENING_GUESSES);
+        assert_eq!(opening[0], ("crate".to_string(), 2.0));
+    }
+
+    /// A word list written to a temporary file, which is removed when this
+    /// is dropped.
+    struct TempList(std::path::PathBuf);
+
+    impl TempList {
+        fn new(name: &str, contents: &str) -> Self {
+            let path = std::env::temp_dir()
+                .join(format!("wordlers-test-{}-{name}.txt", std::process::id()));
+            fs::write(&path, contents).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempList {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn loads_custom_lists() {
+        let answers = TempList::new("answers", "crate\n# comment\n\nTRAIT\n");
+        let words = Words::load(5, Some(&answers.0), None).unwrap();
+        assert_eq!(words.answers, vec!["crate", "trait"]);
+        assert!(words.valid_guess("trait"));
+        assert!(words.valid_guess("heart"));
+
+        let guesses = TempList::new("guesses", "crate\ntrait\nmacro\n");
+        let words = Words::load(5, Some(&answers.0), Some(&guesses.0)).unwrap();
+        assert!(words.valid_guess("macro"));
+        assert!(!words.valid_guess("heart"));
+
+        let missing = Path::new("/nonexistent/wordlers.txt");
+        let err = Words::load(5, Some(missing), None).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn reports_problems_in_custom_lists() {
+        let answers = "crate\nimpl\nself!\ncrate\nasync\n";
+        let err = Words::parse(5, Some(answers), Some("crate\nself!\n"))
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "problems in word lists:\n  \
+             answers:2: 'impl' has 4 letters, expected 5\n  \
+             answers:3: 'self!' contains non-alphabetic characters\n  \
+             answers:4: 'crate' is a duplicate of line 1\n  \
+             guesses:2: 'self!' contains non-alphabetic characters\n  \
+             answers:5: answer 'async' is missing from guesses"
+        );
+
+        let err = Words::parse(5, Some("# nothing here\n"), None)
+            .err()
+            .unwrap();
+        assert!(err.to_string().ends_with("no usable answers"));
     }
 
     #[test]
     fn validates_guesses() {
-        let words = Words::new();
+        let words = Words::new(5);
         assert!(words.valid_guess("heart"));
         assert!(!words.valid_guess("abcde"));
     }